@@ -0,0 +1,139 @@
+use std::{
+	fs,
+	io::{self, Write},
+	path::{Path, PathBuf},
+	process::Command,
+};
+
+pub const MAX_ATTACHMENT_SIZE: usize = 8 * 1024 * 1024;
+
+#[derive(thiserror::Error, Debug)]
+pub enum AttachmentError {
+	#[error("The file is {}, attachments can be at most {}.", format_size(*.0), format_size(MAX_ATTACHMENT_SIZE))]
+	TooLarge(usize),
+	#[error("The attachment could not be decoded.")]
+	Decode(#[from] base64::DecodeError),
+	#[error("The attachment could not be found.")]
+	NotFound,
+	#[error("Failed to access the file: {0}")]
+	Io(#[from] std::io::Error),
+}
+
+// check an attachment against the size limit before it's stored in the vault
+pub fn check_size(size: usize) -> Result<(), AttachmentError> {
+	if size > MAX_ATTACHMENT_SIZE {
+		Err(AttachmentError::TooLarge(size))
+	} else {
+		Ok(())
+	}
+}
+
+// read a file to attach it to an entry and return its name and contents
+pub fn read_file(path: &Path) -> Result<(String, Vec<u8>), AttachmentError> {
+	check_size(fs::metadata(path)?.len() as usize)?;
+
+	let name = path
+		.file_name()
+		.map(|name| name.to_string_lossy().to_string())
+		.unwrap_or(String::from("attachment"));
+	Ok((name, fs::read(path)?))
+}
+
+// write a decrypted attachment to disk
+pub fn write_file(path: &Path, data: &[u8]) -> Result<(), AttachmentError> {
+	fs::write(path, data)?;
+	Ok(())
+}
+
+// the dir decrypted copies are opened from, each process has its own
+fn temp_dir() -> PathBuf {
+	std::env::temp_dir().join(format!("vault-{}", std::process::id()))
+}
+
+// a dir only the current user can read
+fn create_private_dir(dir: &Path) -> io::Result<()> {
+	#[cfg(unix)]
+	{
+		use std::os::unix::fs::{DirBuilderExt, PermissionsExt};
+
+		fs::DirBuilder::new().recursive(true).mode(0o700).create(dir)?;
+		// the dir may be left over from an earlier process with the same id
+		if !fs::symlink_metadata(dir)?.is_dir() {
+			return Err(io::Error::new(
+				io::ErrorKind::AlreadyExists,
+				"the temp path is not a directory",
+			));
+		}
+		fs::set_permissions(dir, fs::Permissions::from_mode(0o700))
+	}
+	#[cfg(not(unix))]
+	fs::create_dir_all(dir)
+}
+
+// a file only the current user can read
+fn write_private_file(path: &Path, data: &[u8]) -> io::Result<()> {
+	let mut options = fs::OpenOptions::new();
+	options.write(true).create(true).truncate(true);
+	#[cfg(unix)]
+	{
+		use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+
+		options.mode(0o600);
+		let file = options.open(path)?;
+		// the mode is only used when the file is created
+		file.set_permissions(fs::Permissions::from_mode(0o600))?;
+		write_all(file, data)
+	}
+	#[cfg(not(unix))]
+	write_all(options.open(path)?, data)
+}
+
+fn write_all(mut file: fs::File, data: &[u8]) -> io::Result<()> {
+	file.write_all(data)?;
+	file.sync_all()
+}
+
+// write a decrypted copy into the temp dir and hand it to the default application
+pub fn open_with_default(
+	name: &str,
+	data: &[u8],
+) -> Result<PathBuf, AttachmentError> {
+	let dir = temp_dir();
+	create_private_dir(&dir)?;
+
+	// never let a stored name point outside of the temp dir
+	let name = Path::new(name)
+		.file_name()
+		.map(|name| name.to_os_string())
+		.unwrap_or("attachment".into());
+	let path = dir.join(name);
+	write_private_file(&path, data)?;
+
+	#[cfg(target_os = "macos")]
+	Command::new("open").arg(&path).spawn()?;
+	#[cfg(target_os = "windows")]
+	Command::new("cmd").args(["/C", "start", ""]).arg(&path).spawn()?;
+	#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+	Command::new("xdg-open").arg(&path).spawn()?;
+
+	Ok(path)
+}
+
+// delete the decrypted copies, they must not outlive the unlocked vault
+pub fn remove_temp_files() -> Result<(), AttachmentError> {
+	match fs::remove_dir_all(temp_dir()) {
+		Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(()),
+		result => Ok(result?),
+	}
+}
+
+// a human readable file size
+pub fn format_size(size: usize) -> String {
+	if size < 1024 {
+		format!("{} B", size)
+	} else if size < 1024 * 1024 {
+		format!("{:.1} KB", size as f64 / 1024.0)
+	} else {
+		format!("{:.1} MB", size as f64 / (1024.0 * 1024.0))
+	}
+}
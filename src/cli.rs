@@ -0,0 +1,401 @@
+use anyhow::{anyhow, bail, Result};
+use std::path::Path;
+
+use vault::{
+	config::{get_config_path, Config},
+	db::{format_date, get_timestamp, DbFields, DynamicFieldKind, EntryKind},
+	encryption::{generate_password, DEFAULT_PASSWORD_LENGTH},
+};
+
+const HELP: &str = "Usage: vault <command> [options]
+
+Commands:
+  list                                 List all entries
+  get <title>                          Show the non-secret details of an entry
+  get <title> --field <name>           Print the value of a field, or the
+                                       next code of a TOTP or HOTP field
+  add <title> [--url <url>] [--username <name>] [--password]
+      [--template <name>]              Add a new entry, --password prompts for it
+  templates                            List the templates new entries can use
+  edit <title> --field <name> [--value <value>]
+                                       Edit a field, prompts when no value is given
+  remove <title>                       Delete an entry with all of its history
+  history <title> --field <name> [--reveal]
+                                       Show the history of a field
+  prune [--keep <n>] [--days <n>] [--dry-run]
+                                       Remove old versions from field histories,
+                                       --keep and --days change the vault retention
+  passwords [--max-age <days>]         List passwords by age, the stale ones are
+                                       marked, --max-age changes the vault policy
+  generate [--length <n>]              Print a newly generated password
+  help                                 Show this help
+
+Running vault without a command starts the GUI.";
+
+fn take_option(args: &mut Vec<String>, name: &str) -> Result<Option<String>> {
+	match args.iter().position(|arg| arg == name) {
+		Some(pos) => {
+			if pos + 1 >= args.len() {
+				bail!("Missing value for {}", name);
+			}
+			let value = args.remove(pos + 1);
+			args.remove(pos);
+			Ok(Some(value))
+		}
+		None => Ok(None),
+	}
+}
+
+fn take_flag(args: &mut Vec<String>, name: &str) -> bool {
+	match args.iter().position(|arg| arg == name) {
+		Some(pos) => {
+			args.remove(pos);
+			true
+		}
+		None => false,
+	}
+}
+
+fn take_title(args: &mut Vec<String>) -> Result<String> {
+	if args.is_empty() {
+		bail!("Missing entry title");
+	}
+	Ok(args.remove(0))
+}
+
+fn check_empty(args: &[String]) -> Result<()> {
+	match args.first() {
+		Some(arg) => bail!("Unexpected argument \"{}\"", arg),
+		None => Ok(()),
+	}
+}
+
+// open the config file, the first run writes a new one
+pub fn load_config() -> Result<Config> {
+	let path = get_config_path();
+	if Path::new(&path).exists() {
+		Config::open(path)
+	} else {
+		eprintln!("writing new config");
+		// TODO: start onboarding flow (new password)
+		Config::create(path)
+	}
+}
+
+fn unlock() -> Result<(Config, String)> {
+	let mut config = load_config()?;
+	let password = rpassword::prompt_password("Password: ")?;
+	config.decrypt_database(password.clone())?;
+	Ok((config, password))
+}
+
+fn find_entry(config: &Config, title: &str) -> Result<usize> {
+	config
+		.db
+		.read()
+		.get_id_by_title(title)
+		.ok_or_else(|| anyhow!("No entry found with the title \"{}\"", title))
+}
+
+fn find_field(config: &Config, id: &usize, name: &str) -> Result<DbFields> {
+	config
+		.db
+		.read()
+		.get_field_by_name(id, name)
+		.ok_or_else(|| anyhow!("No field found with the name \"{}\"", name))
+}
+
+fn describe_date(date: Option<u64>) -> String {
+	date.map(format_date).unwrap_or_else(|| String::from("unknown date"))
+}
+
+fn list(args: Vec<String>) -> Result<()> {
+	check_empty(&args)?;
+	let (config, _) = unlock()?;
+
+	for (id, title, _) in config.db.read().get_list() {
+		println!("{}\t{}", id, title);
+	}
+	Ok(())
+}
+
+fn templates(args: Vec<String>) -> Result<()> {
+	check_empty(&args)?;
+	let (config, _) = unlock()?;
+
+	for template in config.db.read().get_templates() {
+		let fields = template
+			.fields
+			.iter()
+			.map(|field| format!("{} ({})", field.title, field.kind))
+			.collect::<Vec<String>>();
+		println!("{}\t{}\t{}", template.title, template.kind, fields.join(", "));
+	}
+	Ok(())
+}
+
+fn get(mut args: Vec<String>) -> Result<()> {
+	let field_name = take_option(&mut args, "--field")?;
+	let title = take_title(&mut args)?;
+	check_empty(&args)?;
+	let (mut config, password) = unlock()?;
+	let id = find_entry(&config, &title)?;
+
+	match field_name {
+		Some(name) => {
+			let field = find_field(&config, &id, &name)?;
+			let kind = config.db.read().get_dyn_field_kind(&id, &field);
+			match kind {
+				Some(DynamicFieldKind::Totp) => {
+					let (code, _) =
+						config.db.read().get_totp_code(&id, &field, get_timestamp())?;
+					println!("{}", code);
+				}
+				Some(DynamicFieldKind::Hotp) => {
+					let code = config.db.write().generate_hotp_code(&id, &field)?;
+					config.save_database(password)?;
+					println!("{}", code);
+				}
+				_ => println!("{}", config.db.read().get_last_by_field(&id, &field)),
+			}
+		}
+		None => {
+			let db = config.db.read();
+			let entry = db.get_by_id(&id);
+			let kind = db.get_entry_kind(&id);
+			println!("Title:    {}", entry.title);
+			println!("Kind:     {}", kind);
+			if kind == EntryKind::Login {
+				println!("URL:      {}", entry.url);
+			}
+			println!("Tags:     {}", db.get_tags(&id).join(", "));
+			println!("Created:  {}", describe_date(db.get_created(&id)));
+			println!("Modified: {}", describe_date(db.get_modified(&id)));
+			if let Some(last_used) = db.get_last_used(&id) {
+				println!("Used:     {}", format_date(last_used));
+			}
+			if let Some(expires) = db.get_expiry(&id) {
+				println!("Expires:  {}", format_date(expires));
+			}
+			let mut fields = db
+				.get_dyn_fields(&id)
+				.iter()
+				.map(|field| db.get_name_of_dyn_field(&id, field))
+				.collect::<Vec<String>>();
+			if kind == EntryKind::Login {
+				fields.insert(0, String::from("Username, Password"));
+			}
+			for (idx, field) in fields.iter().enumerate() {
+				if idx == 0 {
+					println!("Fields:   {}", field);
+				} else {
+					println!("          {}", field);
+				}
+			}
+		}
+	}
+	Ok(())
+}
+
+fn add(mut args: Vec<String>) -> Result<()> {
+	let url = take_option(&mut args, "--url")?;
+	let username = take_option(&mut args, "--username")?;
+	let ask_password = take_flag(&mut args, "--password");
+	let template = take_option(&mut args, "--template")?;
+	let title = take_title(&mut args)?;
+	check_empty(&args)?;
+	let (mut config, password) = unlock()?;
+
+	let template = match template {
+		Some(name) => Some(
+			config
+				.db
+				.read()
+				.get_template(&name)
+				.ok_or_else(|| anyhow!("No template named \"{}\"", name))?,
+		),
+		None => None,
+	};
+
+	let new_password = if ask_password {
+		Some(rpassword::prompt_password("New entry password: ")?)
+	} else {
+		None
+	};
+
+	{
+		let mut db = config.db.write();
+		let id = match template {
+			Some(template) => db.add_from_template(title, &template),
+			None => db.add(title),
+		};
+		if let Some(url) = url {
+			db.edit_field(id, &DbFields::Url, url);
+		}
+		if let Some(username) = username {
+			db.edit_field(id, &DbFields::Username, username);
+		}
+		if let Some(new_password) = new_password {
+			db.edit_field(id, &DbFields::Password, new_password);
+		}
+	}
+
+	config.save_database(password)?;
+	Ok(())
+}
+
+fn edit(mut args: Vec<String>) -> Result<()> {
+	let field_name = take_option(&mut args, "--field")?
+		.ok_or_else(|| anyhow!("Missing --field option"))?;
+	let value = take_option(&mut args, "--value")?;
+	let title = take_title(&mut args)?;
+	check_empty(&args)?;
+	let (mut config, password) = unlock()?;
+	let id = find_entry(&config, &title)?;
+	let field = find_field(&config, &id, &field_name)?;
+
+	let value = match value {
+		Some(value) => value,
+		None => rpassword::prompt_password(format!("New {}: ", field_name))?,
+	};
+
+	config.db.write().edit_field(id, &field, value);
+	config.save_database(password)?;
+	Ok(())
+}
+
+fn remove(mut args: Vec<String>) -> Result<()> {
+	let title = take_title(&mut args)?;
+	check_empty(&args)?;
+	let (mut config, password) = unlock()?;
+	let id = find_entry(&config, &title)?;
+
+	config.db.write().remove_entry(&id);
+	config.save_database(password)?;
+	Ok(())
+}
+
+fn history(mut args: Vec<String>) -> Result<()> {
+	let field_name = take_option(&mut args, "--field")?
+		.ok_or_else(|| anyhow!("Missing --field option"))?;
+	let reveal = take_flag(&mut args, "--reveal");
+	let title = take_title(&mut args)?;
+	check_empty(&args)?;
+	let (config, _) = unlock()?;
+	let id = find_entry(&config, &title)?;
+	let field = find_field(&config, &id, &field_name)?;
+
+	let history = config
+		.db
+		.read()
+		.get_history(&id, &field)
+		.ok_or_else(|| anyhow!("The field \"{}\" has no history", field_name))?;
+
+	for (date, value) in history {
+		if reveal {
+			println!("{}\t{}", describe_date(date), value);
+		} else {
+			println!("{}", describe_date(date));
+		}
+	}
+	Ok(())
+}
+
+fn prune(mut args: Vec<String>) -> Result<()> {
+	let keep = match take_option(&mut args, "--keep")? {
+		Some(keep) => match keep.parse::<usize>()? {
+			0 => bail!("Keep at least one version"),
+			keep => Some(keep),
+		},
+		None => None,
+	};
+	let days = take_option(&mut args, "--days")?
+		.map(|days| days.parse::<u64>())
+		.transpose()?;
+	let dry_run = take_flag(&mut args, "--dry-run");
+	check_empty(&args)?;
+	let (mut config, password) = unlock()?;
+
+	if keep.is_some() || days.is_some() {
+		let mut retention = config.db.read().get_retention();
+		if keep.is_some() {
+			retention.max_versions = keep;
+		}
+		if days.is_some() {
+			retention.max_age_days = days;
+		}
+		config.db.write().set_retention(retention);
+	}
+
+	let count = config.db.read().count_prunable(get_timestamp());
+	println!("{}", config.db.read().get_retention());
+	if dry_run {
+		println!("{} versions would be removed", count);
+	} else {
+		config.save_database(password)?;
+		println!("Removed {} versions", count);
+	}
+	Ok(())
+}
+
+fn passwords(mut args: Vec<String>) -> Result<()> {
+	let max_age = take_option(&mut args, "--max-age")?;
+	check_empty(&args)?;
+	let (mut config, password) = unlock()?;
+
+	if let Some(max_age) = max_age {
+		let days = max_age.parse::<u64>()?;
+		config.db.write().set_password_max_age((days > 0).then_some(days));
+		config.save_database(password)?;
+	}
+
+	for age in config.db.read().get_password_ages(get_timestamp()) {
+		let days = match age.days {
+			Some(days) => format!("{} days", days),
+			None => String::from("unknown"),
+		};
+		let stale = if age.is_stale { "rotate" } else { "" };
+		println!("{}\t{}\t{}", age.title, days, stale);
+	}
+	Ok(())
+}
+
+fn generate(mut args: Vec<String>) -> Result<()> {
+	let length = match take_option(&mut args, "--length")? {
+		Some(length) => length.parse::<usize>()?,
+		None => DEFAULT_PASSWORD_LENGTH,
+	};
+	check_empty(&args)?;
+
+	println!("{}", generate_password(length));
+	Ok(())
+}
+
+pub fn run(mut args: Vec<String>) -> i32 {
+	let command = args.remove(0);
+	let result = match command.as_str() {
+		"list" => list(args),
+		"get" => get(args),
+		"add" => add(args),
+		"templates" => templates(args),
+		"prune" => prune(args),
+		"passwords" => passwords(args),
+		"edit" => edit(args),
+		"remove" => remove(args),
+		"history" => history(args),
+		"generate" => generate(args),
+		"help" | "--help" | "-h" => {
+			println!("{}", HELP);
+			Ok(())
+		}
+		other => Err(anyhow!("Unknown command \"{}\"\n\n{}", other, HELP)),
+	};
+
+	match result {
+		Ok(()) => 0,
+		Err(error) => {
+			eprintln!("Error: {:#}", error);
+			1
+		}
+	}
+}
@@ -1,8 +1,9 @@
 use crate::{
-	db::{Db, DbEntry},
-	encryption::decrypt_vault,
+	db::{get_timestamp, Db, DbEntry, DbGroup, Retention, SortOrder},
+	encryption::{decrypt_vault, encrypt_vault},
+	template::EntryTemplate,
 };
-use anyhow::Result;
+use anyhow::{Context, Result};
 use parking_lot::RwLock;
 use serde::{Deserialize, Serialize};
 use std::{env, fs, sync::Arc};
@@ -13,7 +14,7 @@ struct ConfigFile {
 	pub db: ConfigFileDb,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
 struct ConfigFileDb {
 	pub cypher: String,
 	pub salt: String,
@@ -23,6 +24,16 @@ struct ConfigFileDb {
 #[derive(Debug, Deserialize, Serialize)]
 struct ConfigFileCypher {
 	pub contents: Vec<DbEntry>,
+	#[serde(default)]
+	pub groups: Vec<DbGroup>,
+	#[serde(default)]
+	pub templates: Vec<EntryTemplate>,
+	#[serde(default)]
+	pub retention: Retention,
+	#[serde(default)]
+	pub password_max_age: Option<u64>,
+	#[serde(default)]
+	pub next_id: usize,
 }
 
 #[derive(Clone, Debug, Serialize)]
@@ -34,6 +45,8 @@ pub struct Config {
 	#[serde(with = "arc_rwlock_serde")]
 	config_db: Arc<RwLock<ConfigFileDb>>,
 	pub vault_unlocked: bool,
+	#[serde(skip)]
+	config_path: String,
 }
 
 mod arc_rwlock_serde {
@@ -59,10 +72,12 @@ mod arc_rwlock_serde {
 	// }
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct ConfigGeneral {
 	pub something: bool,
 	pub db_timeout: f64,
+	#[serde(default)]
+	pub sort_order: SortOrder,
 }
 
 impl Default for Config {
@@ -71,6 +86,7 @@ impl Default for Config {
 			general: Arc::new(RwLock::new(ConfigGeneral {
 				something: true,
 				db_timeout: 900.0,
+				sort_order: SortOrder::default(),
 			})),
 			db: Arc::new(RwLock::new(Db::default())),
 			vault_unlocked: false,
@@ -79,6 +95,7 @@ impl Default for Config {
 				salt: "".to_string(),
 				encrypted: false,
 			})),
+			config_path: get_config_path(),
 		}
 	}
 }
@@ -89,6 +106,7 @@ impl From<ConfigFile> for Config {
 			general: Arc::new(RwLock::new(ConfigGeneral {
 				something: config_file.general.something,
 				db_timeout: config_file.general.db_timeout,
+				sort_order: config_file.general.sort_order,
 			})),
 			vault_unlocked: false,
 			db: Arc::new(RwLock::new(Db::default())),
@@ -97,33 +115,47 @@ impl From<ConfigFile> for Config {
 				encrypted: config_file.db.encrypted,
 				salt: config_file.db.salt,
 			})),
+			config_path: get_config_path(),
 		}
 	}
 }
 
+// the config file in the current working dir
+pub fn get_config_path() -> String {
+	let cwd = match env::current_dir() {
+		Ok(path) => format!("{}", path.display()), // default to current working dir
+		Err(_) => String::from(""),                // fallback to root dir
+	};
+
+	format!("{}/vault_config.toml", cwd)
+}
+
 impl Config {
-	pub fn new() -> Self {
-		let cwd = match env::current_dir() {
-			Ok(path) => format!("{}", path.display()), // default to current working dir
-			Err(_) => String::from(""),                // fallback to root dir
-		};
+	pub fn new() -> Result<Self> {
+		Self::open(get_config_path())
+	}
 
-		let path = format!("{}/vault_config.toml", cwd);
-		match fs::read_to_string(&path) {
-			Ok(content) => {
-				let file_contents: ConfigFile = toml::from_str(&content).unwrap();
-				file_contents.into()
-			}
-			Err(_) => {
-				println!("writing new config");
-				// TODO: start onboarding flow (new password)
-				let config = Config::default();
-				match fs::write(&path, toml::to_string_pretty(&config).unwrap()) {
-					Ok(_) => config,
-					Err(_) => panic!("Can't write config file"),
-				}
-			}
-		}
+	pub fn open(path: String) -> Result<Self> {
+		let content = fs::read_to_string(&path)
+			.with_context(|| format!("Can't read the config file {}", path))?;
+		let file_contents: ConfigFile = toml::from_str(&content)
+			.with_context(|| format!("Can't parse the config file {}", path))?;
+		let mut config = Config::from(file_contents);
+		config.config_path = path;
+		Ok(config)
+	}
+
+	// write a new config file with the default settings, the vault stays
+	// unencrypted until a password is set
+	pub fn create(path: String) -> Result<Self> {
+		let mut config = Config {
+			config_path: path.clone(),
+			..Config::default()
+		};
+		config
+			.save_database(String::new())
+			.with_context(|| format!("Can't write the config file {}", path))?;
+		Ok(config)
 	}
 
 	pub fn decrypt_database(&mut self, password: String) -> Result<()> {
@@ -140,6 +172,44 @@ impl Config {
 			toml::from_str::<ConfigFileCypher>(&self.config_db.read().cypher.clone())?
 		};
 		self.db.write().contents = contents.contents;
+		self.db.write().groups = contents.groups;
+		self.db.write().templates = contents.templates;
+		self.db.write().retention = contents.retention;
+		self.db.write().password_max_age = contents.password_max_age;
+		self.db.write().next_id = contents.next_id;
+		self.db.write().migrate_timestamps();
+		Ok(())
+	}
+
+	pub fn save_database(&mut self, password: String) -> Result<()> {
+		self.db.write().prune_history(get_timestamp());
+
+		let contents = toml::to_string_pretty(&ConfigFileCypher {
+			contents: self.db.read().contents.clone(),
+			groups: self.db.read().groups.clone(),
+			templates: self.db.read().templates.clone(),
+			retention: self.db.read().retention,
+			password_max_age: self.db.read().password_max_age,
+			next_id: self.db.read().next_id,
+		})?;
+
+		let cypher = if self.config_db.read().encrypted {
+			encrypt_vault(contents, password, self.config_db.read().salt.clone())?
+		} else {
+			contents
+		};
+		self.config_db.write().cypher = cypher;
+
+		self.save_general()
+	}
+
+	// write the settings to disk, the vault is stored as it was last saved
+	pub fn save_general(&self) -> Result<()> {
+		let config_file = ConfigFile {
+			general: self.general.read().clone(),
+			db: self.config_db.read().clone(),
+		};
+		fs::write(&self.config_path, toml::to_string_pretty(&config_file)?)?;
 		Ok(())
 	}
 
@@ -162,3 +232,22 @@ impl Config {
 	// 	false
 	// }
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn created_configs_can_be_opened() {
+		let path = env::temp_dir()
+			.join(format!("vault_config_{}.toml", std::process::id()))
+			.display()
+			.to_string();
+		let created = Config::create(path.clone()).unwrap();
+
+		let mut opened = Config::open(path.clone()).unwrap();
+		opened.decrypt_database(String::new()).unwrap();
+		fs::remove_file(&path).unwrap();
+		assert_eq!(opened.db.read().get_list(), created.db.read().get_list());
+	}
+}
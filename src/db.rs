@@ -1,35 +1,671 @@
 use serde::{Deserialize, Serialize};
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
-type SecureField = (u64, String);
+use base64::{engine::general_purpose, Engine as _};
+
+use crate::{
+	attachment::{check_size, AttachmentError},
+	otp::{Hotp, OtpError, Totp},
+	search::{fuzzy_score, parse_query, recency_score, QueryTerm},
+	template::{builtin_templates, EntryTemplate, TemplateError},
+};
+
+// a version of a field, the date is none for versions written before dates
+// were recorded
+type SecureField = (Option<u64>, String);
+
+// entries count as expiring this many days before their expiry date
+pub const EXPIRY_WARNING_DAYS: u64 = 14;
+// how many changes of a session can be undone
+pub const UNDO_LIMIT: usize = 100;
+
+#[derive(
+	Debug, Default, Deserialize, Serialize, Copy, Clone, PartialEq, Eq,
+)]
+pub enum DynamicFieldKind {
+	Text,
+	// fields from before kinds existed were all treated as secrets
+	#[default]
+	Secret,
+	Note,
+	Url,
+	Email,
+	Phone,
+	Date,
+	Number,
+	Totp,
+	Hotp,
+}
+
+impl DynamicFieldKind {
+	pub const ALL: [DynamicFieldKind; 10] = [
+		DynamicFieldKind::Text,
+		DynamicFieldKind::Secret,
+		DynamicFieldKind::Note,
+		DynamicFieldKind::Url,
+		DynamicFieldKind::Email,
+		DynamicFieldKind::Phone,
+		DynamicFieldKind::Date,
+		DynamicFieldKind::Number,
+		DynamicFieldKind::Totp,
+		DynamicFieldKind::Hotp,
+	];
+
+	// secret fields are masked in the UI and only searched on request
+	pub fn is_secret(&self) -> bool {
+		match self {
+			DynamicFieldKind::Secret
+			| DynamicFieldKind::Note
+			| DynamicFieldKind::Totp
+			| DynamicFieldKind::Hotp => true,
+			DynamicFieldKind::Text
+			| DynamicFieldKind::Url
+			| DynamicFieldKind::Email
+			| DynamicFieldKind::Phone
+			| DynamicFieldKind::Date
+			| DynamicFieldKind::Number => false,
+		}
+	}
+
+	// check if a value can be stored in a field of this kind
+	pub fn is_valid(&self, value: &str) -> bool {
+		let value = value.trim();
+		match self {
+			DynamicFieldKind::Text
+			| DynamicFieldKind::Secret
+			| DynamicFieldKind::Note => true,
+			DynamicFieldKind::Url => {
+				!value.contains(char::is_whitespace)
+					&& (value.contains("://") || value.contains('.'))
+			}
+			DynamicFieldKind::Email => match value.split_once('@') {
+				Some((user, domain)) => {
+					!user.is_empty()
+						&& !value.contains(char::is_whitespace)
+						&& !domain.contains('@')
+						&& domain.contains('.')
+						&& !domain.starts_with('.')
+						&& !domain.ends_with('.')
+				}
+				None => false,
+			},
+			DynamicFieldKind::Phone => {
+				value.chars().filter(|c| c.is_ascii_digit()).count() >= 3
+					&& value.chars().all(|c| c.is_ascii_digit() || " +-().".contains(c))
+			}
+			DynamicFieldKind::Date => {
+				chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d").is_ok()
+			}
+			DynamicFieldKind::Number => value.parse::<f64>().is_ok(),
+			DynamicFieldKind::Totp => Totp::parse(value).is_ok(),
+			DynamicFieldKind::Hotp => Hotp::parse(value).is_ok(),
+		}
+	}
+
+	// the value that ends up in the clipboard when a field of this kind is copied
+	pub fn copy_value(&self, value: &str) -> String {
+		match self {
+			DynamicFieldKind::Phone => {
+				value.chars().filter(|c| c.is_ascii_digit() || *c == '+').collect()
+			}
+			DynamicFieldKind::Url
+			| DynamicFieldKind::Email
+			| DynamicFieldKind::Date
+			| DynamicFieldKind::Number => String::from(value.trim()),
+			_ => String::from(value),
+		}
+	}
+
+	// a link the UI can open for kinds that point somewhere
+	pub fn link(&self, value: &str) -> Option<String> {
+		let value = value.trim();
+		if value.is_empty() {
+			return None;
+		}
+		match self {
+			DynamicFieldKind::Url if value.contains("://") => {
+				Some(String::from(value))
+			}
+			DynamicFieldKind::Url => Some(format!("https://{}", value)),
+			DynamicFieldKind::Email => Some(format!("mailto:{}", value)),
+			DynamicFieldKind::Phone => {
+				Some(format!("tel:{}", self.copy_value(value)))
+			}
+			_ => None,
+		}
+	}
+}
+
+impl std::fmt::Display for DynamicFieldKind {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		match *self {
+			DynamicFieldKind::Text => write!(f, "Text"),
+			DynamicFieldKind::Secret => write!(f, "Secret"),
+			DynamicFieldKind::Note => write!(f, "Note"),
+			DynamicFieldKind::Url => write!(f, "URL"),
+			DynamicFieldKind::Email => write!(f, "Email"),
+			DynamicFieldKind::Phone => write!(f, "Phone"),
+			DynamicFieldKind::Date => write!(f, "Date"),
+			DynamicFieldKind::Number => write!(f, "Number"),
+			DynamicFieldKind::Totp => write!(f, "TOTP"),
+			DynamicFieldKind::Hotp => write!(f, "HOTP"),
+		}
+	}
+}
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct DynamicField {
 	id: usize,
 	title: String,
 	visible: bool,
+	#[serde(default)]
+	kind: DynamicFieldKind,
+	#[serde(
+		serialize_with = "serialize_history",
+		deserialize_with = "deserialize_history"
+	)]
 	value: Vec<SecureField>,
 }
 
+impl DynamicField {
+	pub fn new(
+		id: usize,
+		title: String,
+		kind: DynamicFieldKind,
+		value: String,
+	) -> Self {
+		Self {
+			id,
+			title,
+			visible: true,
+			kind,
+			value: vec![(Some(get_timestamp()), value)],
+		}
+	}
+}
+
 impl Default for DynamicField {
 	fn default() -> Self {
 		Self {
 			id: 0,
 			title: String::from(""),
 			visible: true,
-			value: vec![(0, String::from(""))],
+			kind: DynamicFieldKind::Secret,
+			value: vec![(None, String::from(""))],
+		}
+	}
+}
+
+#[derive(
+	Debug, Default, Deserialize, Serialize, Copy, Clone, PartialEq, Eq,
+)]
+pub enum EntryKind {
+	#[default]
+	Login,
+	// an entry without URL, username and password
+	SecureNote,
+}
+
+impl std::fmt::Display for EntryKind {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		match *self {
+			EntryKind::Login => write!(f, "Login"),
+			EntryKind::SecureNote => write!(f, "Secure Note"),
 		}
 	}
 }
 
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct Attachment {
+	id: usize,
+	name: String,
+	// base64 encoded file contents, the vault is encrypted as a whole
+	#[serde(
+		serialize_with = "serialize_history",
+		deserialize_with = "deserialize_history"
+	)]
+	value: Vec<SecureField>,
+}
+
+impl Attachment {
+	fn size_of(value: &SecureField) -> usize {
+		value.1.len() * 3 / 4
+	}
+}
+
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct DbEntry {
 	pub id: usize,
-	pub title: String,
-	pub url: String,
+	#[serde(
+		serialize_with = "serialize_history",
+		deserialize_with = "string_or_history"
+	)]
+	pub title: Vec<SecureField>,
+	#[serde(
+		serialize_with = "serialize_history",
+		deserialize_with = "string_or_history"
+	)]
+	pub url: Vec<SecureField>,
+	#[serde(
+		serialize_with = "serialize_history",
+		deserialize_with = "deserialize_history"
+	)]
 	pub username: Vec<SecureField>,
+	#[serde(
+		serialize_with = "serialize_history",
+		deserialize_with = "deserialize_history"
+	)]
 	pub password: Vec<SecureField>,
 	pub fields: Vec<DynamicField>,
+	#[serde(default)]
+	pub group: Option<usize>,
+	#[serde(default)]
+	pub tags: Vec<String>,
+	#[serde(default)]
+	pub favorite: bool,
+	#[serde(default)]
+	pub last_used: u64,
+	#[serde(default)]
+	pub attachments: Vec<Attachment>,
+	#[serde(default)]
+	pub kind: EntryKind,
+	// retention settings of single fields that override the vault wide one
+	#[serde(default)]
+	pub retention: Vec<FieldRetention>,
+	// none when the entry was stored before these were recorded
+	#[serde(default)]
+	pub created: Option<u64>,
+	#[serde(default)]
+	pub modified: Option<u64>,
+	// the date the credentials of the entry should be rotated by
+	#[serde(default)]
+	pub expires: Option<u64>,
+	// days until the password should be changed, overrides the vault wide one
+	#[serde(default)]
+	pub password_max_age: Option<u64>,
+	// the ids the next field and attachment get, ids of deleted ones are never
+	// handed out again
+	#[serde(default)]
+	pub next_field_id: usize,
+	#[serde(default)]
+	pub next_attachment_id: usize,
+}
+
+#[derive(
+	Debug, Default, Deserialize, Serialize, Copy, Clone, PartialEq, Eq,
+)]
+pub enum SortOrder {
+	Favorites,
+	Recent,
+	Title,
+	#[default]
+	Created,
+}
+
+impl SortOrder {
+	pub const ALL: [SortOrder; 4] = [
+		SortOrder::Favorites,
+		SortOrder::Recent,
+		SortOrder::Title,
+		SortOrder::Created,
+	];
+}
+
+impl std::fmt::Display for SortOrder {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		match *self {
+			SortOrder::Favorites => write!(f, "Favorites"),
+			SortOrder::Recent => write!(f, "Recent"),
+			SortOrder::Title => write!(f, "Title"),
+			SortOrder::Created => write!(f, "Created"),
+		}
+	}
+}
+
+// how many old versions of a field to keep, unset limits keep everything
+#[derive(
+	Debug, Default, Deserialize, Serialize, Copy, Clone, PartialEq, Eq,
+)]
+pub struct Retention {
+	pub max_versions: Option<usize>,
+	pub max_age_days: Option<u64>,
+}
+
+impl Retention {
+	pub fn is_unlimited(&self) -> bool {
+		self.max_versions.is_none() && self.max_age_days.is_none()
+	}
+
+	// split a history into the versions to keep, the current value always stays
+	fn keep(&self, history: &[SecureField], now: u64) -> Vec<SecureField> {
+		let min_date =
+			self.max_age_days.map(|days| now.saturating_sub(days * 24 * 60 * 60));
+		let len = history.len();
+
+		history
+			.iter()
+			.enumerate()
+			.filter(|(idx, (date, _))| {
+				let age = len - 1 - idx;
+				// versions without a date are older than any limit
+				age == 0
+					|| (self.max_versions.is_none_or(|max| age < max)
+						&& min_date.is_none_or(|min| date.is_some_and(|date| date >= min)))
+			})
+			.map(|(_, item)| item.clone())
+			.collect()
+	}
+}
+
+impl std::fmt::Display for Retention {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		match (self.max_versions, self.max_age_days) {
+			(None, None) => write!(f, "Keep all versions"),
+			(Some(versions), None) => {
+				write!(f, "Keep the last {} versions", versions)
+			}
+			(None, Some(days)) => {
+				write!(f, "Keep versions of the last {} days", days)
+			}
+			(Some(versions), Some(days)) => write!(
+				f,
+				"Keep the last {} versions of the last {} days",
+				versions, days
+			),
+		}
+	}
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct FieldRetention {
+	pub field: DbFields,
+	pub retention: Retention,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct DbGroup {
+	pub id: usize,
+	pub title: String,
+	pub parent: Option<usize>,
+}
+
+fn last_value(field: &[SecureField]) -> &str {
+	field.last().map(|item| item.1.as_str()).unwrap_or("")
+}
+
+// how the date of a version is stored, toml has no way to write a missing value
+#[derive(Deserialize, Serialize)]
+#[serde(untagged)]
+enum StoredDate {
+	Date(u64),
+	Unknown(String),
+}
+
+impl From<StoredDate> for Option<u64> {
+	fn from(date: StoredDate) -> Self {
+		match date {
+			// older vaults stored 0 for versions without a date
+			StoredDate::Date(0) | StoredDate::Unknown(_) => None,
+			StoredDate::Date(date) => Some(date),
+		}
+	}
+}
+
+fn serialize_history<S>(
+	history: &[SecureField],
+	serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+	S: serde::Serializer,
+{
+	serializer.collect_seq(history.iter().map(|(date, value)| {
+		let date = match date {
+			Some(date) => StoredDate::Date(*date),
+			None => StoredDate::Unknown(String::from("unknown")),
+		};
+		(date, value)
+	}))
+}
+
+fn deserialize_history<'de, D>(
+	deserializer: D,
+) -> Result<Vec<SecureField>, D::Error>
+where
+	D: serde::Deserializer<'de>,
+{
+	Ok(
+		Vec::<(StoredDate, String)>::deserialize(deserializer)?
+			.into_iter()
+			.map(|(date, value)| (date.into(), value))
+			.collect(),
+	)
+}
+
+// titles and URLs were plain strings before they kept a history
+fn string_or_history<'de, D>(
+	deserializer: D,
+) -> Result<Vec<SecureField>, D::Error>
+where
+	D: serde::Deserializer<'de>,
+{
+	#[derive(Deserialize)]
+	#[serde(untagged)]
+	enum Versioned {
+		Plain(String),
+		History(Vec<(StoredDate, String)>),
+	}
+
+	Ok(match Versioned::deserialize(deserializer)? {
+		Versioned::Plain(value) => vec![(None, value)],
+		Versioned::History(history) => {
+			history.into_iter().map(|(date, value)| (date.into(), value)).collect()
+		}
+	})
+}
+
+// trim tags and drop empty ones and case-insensitive duplicates
+fn clean_tags(tags: Vec<String>) -> Vec<String> {
+	let mut cleaned: Vec<String> = Vec::new();
+	for tag in tags {
+		let tag = tag.trim().to_string();
+		if !tag.is_empty()
+			&& !cleaned.iter().any(|item| item.eq_ignore_ascii_case(&tag))
+		{
+			cleaned.push(tag);
+		}
+	}
+	cleaned
+}
+
+impl DbEntry {
+	// entries from before the counters were stored start after the highest id
+	fn new_field_id(&mut self) -> usize {
+		let id = self
+			.next_field_id
+			.max(self.fields.iter().map(|field| field.id).max().unwrap_or(0) + 1);
+		self.next_field_id = id + 1;
+		id
+	}
+
+	fn new_attachment_id(&mut self) -> usize {
+		let id = self
+			.next_attachment_id
+			.max(self.attachments.iter().map(|item| item.id).max().unwrap_or(0) + 1);
+		self.next_attachment_id = id + 1;
+		id
+	}
+
+	fn retention_of(&self, field: DbFields, vault: Retention) -> Retention {
+		self
+			.retention
+			.iter()
+			.find(|item| item.field == field)
+			.map(|item| item.retention)
+			.unwrap_or(vault)
+	}
+
+	// drop old versions of all fields and return how many were removed and from
+	// which fields
+	fn prune(&mut self, vault: Retention, now: u64) -> (usize, Vec<DbFields>) {
+		let mut removed = 0;
+		let mut pruned = Vec::new();
+		let mut prune_field = |field: DbFields,
+		                       history: &mut Vec<SecureField>,
+		                       retention: Retention| {
+			let kept = retention.keep(history, now);
+			if kept.len() < history.len() {
+				removed += history.len() - kept.len();
+				pruned.push(field);
+			}
+			*history = kept;
+		};
+
+		let retention = self.retention_of(DbFields::Title, vault);
+		prune_field(DbFields::Title, &mut self.title, retention);
+		let retention = self.retention_of(DbFields::Url, vault);
+		prune_field(DbFields::Url, &mut self.url, retention);
+		let retention = self.retention_of(DbFields::Username, vault);
+		prune_field(DbFields::Username, &mut self.username, retention);
+		let retention = self.retention_of(DbFields::Password, vault);
+		prune_field(DbFields::Password, &mut self.password, retention);
+
+		let overrides = self.retention.clone();
+		for field in self.fields.iter_mut() {
+			let retention = overrides
+				.iter()
+				.find(|item| item.field == DbFields::Fields(field.id))
+				.map(|item| item.retention)
+				.unwrap_or(vault);
+			prune_field(DbFields::Fields(field.id), &mut field.value, retention);
+		}
+
+		// attachments don't have retention settings of their own
+		for attachment in self.attachments.iter_mut() {
+			let kept = vault.keep(&attachment.value, now);
+			removed += attachment.value.len() - kept.len();
+			attachment.value = kept;
+		}
+
+		(removed, pruned)
+	}
+
+	// the known dates of every version of every field of this entry
+	fn field_dates(&self) -> impl Iterator<Item = u64> + '_ {
+		self
+			.title
+			.iter()
+			.chain(self.url.iter())
+			.chain(self.username.iter())
+			.chain(self.password.iter())
+			.chain(self.fields.iter().flat_map(|field| field.value.iter()))
+			.filter_map(|item| item.0)
+	}
+
+	// the history of a field, none for the id and missing dynamic fields
+	fn history_mut(&mut self, field: &DbFields) -> Option<&mut Vec<SecureField>> {
+		match field {
+			DbFields::Id => None,
+			DbFields::Title => Some(&mut self.title),
+			DbFields::Url => Some(&mut self.url),
+			DbFields::Username => Some(&mut self.username),
+			DbFields::Password => Some(&mut self.password),
+			DbFields::Fields(field_id) => self
+				.fields
+				.iter_mut()
+				.find(|field| field.id == *field_id)
+				.map(|field| &mut field.value),
+		}
+	}
+
+	// the timestamp of the most recent edit to any field of this entry
+	fn last_edit(&self) -> Option<u64> {
+		self.field_dates().max()
+	}
+
+	// fill in the created and modified dates of entries stored before they were recorded
+	fn migrate_timestamps(&mut self) {
+		if self.created.is_none() {
+			self.created = self.field_dates().min();
+		}
+		if self.modified.is_none() {
+			self.modified = self.last_edit();
+		}
+	}
+
+	// the best score and field for a single search term
+	fn match_term(
+		&self,
+		term: &QueryTerm,
+		include_secrets: bool,
+	) -> Option<(i64, DbFields)> {
+		let fields = self.fields.iter().filter(|field| field.visible);
+
+		match term {
+			QueryTerm::Url(value) => fuzzy_score(value, last_value(&self.url))
+				.map(|score| (score, DbFields::Url)),
+			QueryTerm::User(value) => fuzzy_score(value, last_value(&self.username))
+				.map(|score| (score, DbFields::Username)),
+			QueryTerm::Field { name, value } => fields
+				.filter_map(|field| {
+					let title_score = fuzzy_score(name, &field.title)?;
+					let value_score = match value {
+						Some(value) if include_secrets || !field.kind.is_secret() => {
+							fuzzy_score(value, last_value(&field.value))?
+						}
+						Some(_) => return None,
+						None => 0,
+					};
+					Some((title_score + value_score, DbFields::Fields(field.id)))
+				})
+				.max_by_key(|(score, _)| *score),
+			// tags are filters so they have to match exactly
+			QueryTerm::Tag(value) => self
+				.tags
+				.iter()
+				.any(|tag| tag.eq_ignore_ascii_case(value))
+				.then_some((0, DbFields::Title)),
+			QueryTerm::Text(value) => {
+				let mut candidates = vec![
+					// title matches are worth the most
+					fuzzy_score(value, last_value(&self.title))
+						.map(|score| (score * 2, DbFields::Title)),
+					fuzzy_score(value, last_value(&self.url))
+						.map(|score| (score, DbFields::Url)),
+					fuzzy_score(value, last_value(&self.username))
+						.map(|score| (score, DbFields::Username)),
+				];
+				if include_secrets {
+					candidates.push(
+						fuzzy_score(value, last_value(&self.password))
+							.map(|score| (score, DbFields::Password)),
+					);
+				}
+				for field in fields {
+					candidates.push(
+						fuzzy_score(value, &field.title)
+							.map(|score| (score, DbFields::Fields(field.id))),
+					);
+					if include_secrets || !field.kind.is_secret() {
+						candidates.push(
+							fuzzy_score(value, last_value(&field.value))
+								.map(|score| (score, DbFields::Fields(field.id))),
+						);
+					}
+				}
+
+				candidates.into_iter().flatten().max_by_key(|(score, _)| *score)
+			}
+		}
+	}
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PasswordAge {
+	pub id: usize,
+	pub title: String,
+	// none when the password was set before dates were recorded
+	pub days: Option<u64>,
+	pub is_stale: bool,
 }
 
 #[derive(Debug)]
@@ -39,6 +675,9 @@ pub struct NewDbEntry {
 	pub username: Vec<SecureField>,
 	pub password: Vec<SecureField>,
 	pub fields: Vec<DynamicField>,
+	pub group: Option<usize>,
+	pub tags: Vec<String>,
+	pub kind: EntryKind,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -48,7 +687,7 @@ pub struct DbEntryNonSecure {
 	pub url: String,
 }
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Deserialize, Serialize, Copy, Clone, PartialEq, Eq, Hash)]
 pub enum DbFields {
 	Id,
 	Title,
@@ -71,10 +710,60 @@ impl std::fmt::Display for DbFields {
 	}
 }
 
+// a change to the vault that can be reverted and applied again
+#[derive(Debug, Clone)]
+enum UndoStep {
+	// a value was pushed on top of the history of a field
+	Edit {
+		id: usize,
+		field: DbFields,
+		value: SecureField,
+	},
+	AddField {
+		id: usize,
+		field: DynamicField,
+	},
+	Visibility {
+		id: usize,
+		field_id: usize,
+		visible: bool,
+	},
+	Swap {
+		id: usize,
+		field_ids: (usize, usize),
+	},
+	// the entry is updated every time undo or redo takes it out of the vault
+	AddEntry {
+		index: usize,
+		entry: DbEntry,
+	},
+	RemoveEntry {
+		index: usize,
+		entry: DbEntry,
+	},
+}
+
 #[derive(Debug, Deserialize, Serialize)]
 pub struct Db {
 	pub contents: Vec<DbEntry>,
+	#[serde(default)]
+	pub groups: Vec<DbGroup>,
+	#[serde(default)]
+	pub templates: Vec<EntryTemplate>,
+	#[serde(default)]
+	pub retention: Retention,
+	// days until passwords should be changed, none never asks for it
+	#[serde(default)]
+	pub password_max_age: Option<u64>,
+	// the id the next entry gets, ids of removed entries are never handed out again
+	#[serde(default)]
+	pub next_id: usize,
 	pub timeout: u16,
+	// changes are only undoable within the session they were made in
+	#[serde(skip)]
+	undo_steps: Vec<UndoStep>,
+	#[serde(skip)]
+	redo_steps: Vec<UndoStep>,
 }
 
 impl Default for Db {
@@ -83,23 +772,108 @@ impl Default for Db {
 			timeout: 60,
 			contents: vec![DbEntry {
 				id: 1,
-				title: String::from("Bank"),
-				url: String::from("https://bankofaustralia.com.au"),
-				username: vec![(1702851212, String::from("Dom"))],
-				password: vec![(1702851212, String::from("totally_secure_password!1"))],
+				title: vec![(Some(1702851212), String::from("Bank"))],
+				url: vec![(
+					Some(1702851212),
+					String::from("https://bankofaustralia.com.au"),
+				)],
+				username: vec![(Some(1702851212), String::from("Dom"))],
+				password: vec![(
+					Some(1702851212),
+					String::from("totally_secure_password!1"),
+				)],
 				fields: vec![DynamicField {
 					id: 0,
 					title: String::from("Notes"),
 					visible: true,
-					value: vec![(1702851212, String::from("These are my bank deets"))],
+					kind: DynamicFieldKind::Secret,
+					value: vec![(
+						Some(1702851212),
+						String::from("These are my bank deets"),
+					)],
 				}],
+				group: None,
+				tags: Vec::new(),
+				favorite: false,
+				last_used: 0,
+				attachments: Vec::new(),
+				kind: EntryKind::Login,
+				retention: Vec::new(),
+				created: Some(1702851212),
+				modified: Some(1702851212),
+				expires: None,
+				password_max_age: None,
+				next_field_id: 1,
+				next_attachment_id: 1,
 			}],
+			groups: Vec::new(),
+			templates: Vec::new(),
+			retention: Retention::default(),
+			password_max_age: None,
+			next_id: 2,
+			undo_steps: Vec::new(),
+			redo_steps: Vec::new(),
+		}
+	}
+}
+
+// the current unix timestamp in seconds
+pub fn get_timestamp() -> u64 {
+	SystemTime::now()
+		.duration_since(UNIX_EPOCH)
+		.unwrap_or(Duration::new(0, 0))
+		.as_secs()
+}
+
+// the timestamp of the last second of a local day written as YYYY-MM-DD, so
+// an expiry date is still valid on the day itself
+pub fn parse_date(value: &str) -> Option<u64> {
+	chrono::NaiveDate::parse_from_str(value.trim(), "%Y-%m-%d")
+		.ok()
+		.and_then(|date| date.and_hms_opt(23, 59, 59))
+		.and_then(|datetime| datetime.and_local_timezone(chrono::Local).latest())
+		.and_then(|datetime| u64::try_from(datetime.timestamp()).ok())
+}
+
+// the local day of a timestamp written as YYYY-MM-DD
+pub fn format_date(timestamp: u64) -> String {
+	chrono::DateTime::from_timestamp(timestamp as i64, 0)
+		.map(|datetime| {
+			datetime.with_timezone(&chrono::Local).format("%Y-%m-%d").to_string()
+		})
+		.unwrap_or_default()
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ExpiryStatus {
+	ExpiringSoon,
+	Expired,
+}
+
+impl ExpiryStatus {
+	// none when the entry has no expiry date or it is still far away
+	pub fn of(expires: u64, now: u64) -> Option<Self> {
+		if expires <= now {
+			Some(ExpiryStatus::Expired)
+		} else if expires - now <= EXPIRY_WARNING_DAYS * 24 * 60 * 60 {
+			Some(ExpiryStatus::ExpiringSoon)
+		} else {
+			None
+		}
+	}
+}
+
+impl std::fmt::Display for ExpiryStatus {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		match *self {
+			ExpiryStatus::ExpiringSoon => write!(f, "Expiring soon"),
+			ExpiryStatus::Expired => write!(f, "Expired"),
 		}
 	}
 }
 
 fn to_tuple(item: &DbEntry, idx: usize) -> (usize, &'static str, usize) {
-	(item.id, Box::leak(item.title.clone().into_boxed_str()), idx)
+	(item.id, Box::leak(last_value(&item.title).into()), idx)
 }
 
 impl Db {
@@ -122,13 +896,134 @@ impl Db {
 		} else {
 			DbEntry {
 				id: *id,
-				title: String::from("Not found"),
-				url: String::from(""),
-				username: vec![(0, String::from(""))],
-				password: vec![(0, String::from(""))],
+				title: vec![(None, String::from("Not found"))],
+				url: vec![(None, String::from(""))],
+				username: vec![(None, String::from(""))],
+				password: vec![(None, String::from(""))],
 				fields: vec![DynamicField::default()],
+				group: None,
+				tags: Vec::new(),
+				favorite: false,
+				last_used: 0,
+				attachments: Vec::new(),
+				kind: EntryKind::Login,
+				retention: Vec::new(),
+				created: None,
+				modified: None,
+				expires: None,
+				password_max_age: None,
+				next_field_id: 0,
+				next_attachment_id: 0,
+			}
+		}
+	}
+
+	// get all entries in the order picked for the sidebar
+	fn sorted(&self, order: SortOrder) -> Vec<&DbEntry> {
+		let mut entries = self.contents.iter().rev().collect::<Vec<&DbEntry>>();
+		match order {
+			SortOrder::Favorites => {
+				entries.sort_by_key(|entry| {
+					(
+						std::cmp::Reverse(entry.favorite),
+						std::cmp::Reverse(entry.last_used),
+					)
+				});
+			}
+			SortOrder::Recent => {
+				entries.sort_by_key(|entry| std::cmp::Reverse(entry.last_used));
+			}
+			SortOrder::Title => {
+				entries.sort_by_key(|entry| last_value(&entry.title).to_lowercase());
+			}
+			// entries without a creation date come last, ordered by id
+			SortOrder::Created => {
+				entries
+					.sort_by_key(|entry| std::cmp::Reverse((entry.created, entry.id)));
 			}
 		}
+		entries
+	}
+
+	// search entries ranked by relevance and return the best matching field of each entry
+	pub fn search(
+		&self,
+		query: &str,
+		include_secrets: bool,
+		group: Option<usize>,
+		tags: &[String],
+		order: SortOrder,
+	) -> Vec<(usize, DbFields)> {
+		let mut terms = parse_query(query);
+		terms.extend(tags.iter().map(|tag| QueryTerm::Tag(tag.clone())));
+		let now = get_timestamp();
+		let scope = group.map(|group| self.get_group_tree(&group));
+
+		let mut results = self
+			.sorted(order)
+			.into_iter()
+			.filter(|entry| match (&scope, entry.group) {
+				(None, _) => true,
+				(Some(scope), Some(group)) => scope.contains(&group),
+				(Some(_), None) => false,
+			})
+			.filter_map(|entry| {
+				if terms.is_empty() {
+					return Some((0, entry.id, DbFields::Title));
+				}
+
+				let mut total = 0;
+				let mut best: Option<(i64, DbFields)> = None;
+				for term in &terms {
+					let (score, field) = entry.match_term(term, include_secrets)?;
+					total += score;
+					let is_better = match best {
+						Some((best_score, _)) => score > best_score,
+						None => true,
+					};
+					if is_better {
+						best = Some((score, field));
+					}
+				}
+
+				total += entry.last_edit().map_or(0, |date| recency_score(date, now));
+				best.map(|(_, field)| (total, entry.id, field))
+			})
+			.collect::<Vec<(i64, usize, DbFields)>>();
+
+		// stable sort keeps the chosen order when scores are equal
+		results.sort_by_key(|(score, _, _)| std::cmp::Reverse(*score));
+		results.into_iter().map(|(_, id, field)| (id, field)).collect()
+	}
+
+	// find the id of an entry by its title
+	pub fn get_id_by_title(&self, title: &str) -> Option<usize> {
+		self
+			.contents
+			.iter()
+			.find(|item| last_value(&item.title) == title)
+			.or_else(|| {
+				self.contents.iter().find(|item| {
+					last_value(&item.title).to_lowercase() == title.to_lowercase()
+				})
+			})
+			.map(|item| item.id)
+	}
+
+	// find a field of an entry by its name
+	pub fn get_field_by_name(&self, id: &usize, name: &str) -> Option<DbFields> {
+		match name.to_lowercase().as_str() {
+			"title" => Some(DbFields::Title),
+			"url" => Some(DbFields::Url),
+			"username" => Some(DbFields::Username),
+			"password" => Some(DbFields::Password),
+			name => self
+				.get_by_id_secure(id)
+				.fields
+				.iter()
+				.find(|field| field.title.to_lowercase() == name)
+				.map(|field| DbFields::Fields(field.id)),
+		}
 	}
 
 	// get name of dyn field
@@ -147,8 +1042,8 @@ impl Db {
 
 		DbEntryNonSecure {
 			id: *id,
-			title: entry.title,
-			url: entry.url,
+			title: last_value(&entry.title).to_string(),
+			url: last_value(&entry.url).to_string(),
 		}
 	}
 
@@ -163,31 +1058,95 @@ impl Db {
 				id: *field_id,
 				title: String::from(""),
 				visible: true,
-				value: vec![(0, String::from(""))],
+				kind: DynamicFieldKind::Secret,
+				value: vec![(None, String::from(""))],
 			})
 	}
 
-	// get a list of all dynamic fields
-	pub fn get_dyn_fields(&self, id: &usize) -> Vec<DbFields> {
-		let entry = self.get_by_id_secure(id);
+	// get the kind of a dynamic field
+	pub fn get_dyn_field_kind(
+		&self,
+		id: &usize,
+		field: &DbFields,
+	) -> Option<DynamicFieldKind> {
+		match field {
+			DbFields::Fields(field_id) => {
+				let entry = self.get_by_id_secure(id);
+				Some(self.get_field_by_id(&entry, field_id).kind)
+			}
+			_ => None,
+		}
+	}
 
-		entry
-			.fields
-			.iter()
-			.filter(|field| field.visible)
-			.map(|field| DbFields::Fields(field.id))
-			.collect()
+	// get the kind of an entry
+	pub fn get_entry_kind(&self, id: &usize) -> EntryKind {
+		self.get_by_id_secure(id).kind
 	}
 
-	// get a list of all dynamic fields
-	pub fn get_hidden_dyn_fields(&self, id: &usize) -> Vec<DbFields> {
-		let entry = self.get_by_id_secure(id);
+	// get when an entry was created, none for old entries without a record
+	pub fn get_created(&self, id: &usize) -> Option<u64> {
+		self.get_by_id_secure(id).created
+	}
 
-		entry
-			.fields
-			.iter()
-			.filter(|field| !field.visible)
-			.map(|field| DbFields::Fields(field.id))
+	// get when any field of an entry was last changed
+	pub fn get_modified(&self, id: &usize) -> Option<u64> {
+		self.get_by_id_secure(id).modified
+	}
+
+	// fill in the entry dates of a vault stored by an older version
+	pub fn migrate_timestamps(&mut self) {
+		self.contents.iter_mut().for_each(|entry| entry.migrate_timestamps());
+	}
+
+	// get the current code of a one-time password field and the seconds until it expires
+	pub fn get_totp_code(
+		&self,
+		id: &usize,
+		field: &DbFields,
+		timestamp: u64,
+	) -> Result<(String, u64), OtpError> {
+		if self.get_dyn_field_kind(id, field) != Some(DynamicFieldKind::Totp) {
+			return Err(OtpError::NotAnOtpField);
+		}
+
+		let totp = Totp::parse(&self.get_last_by_field(id, field))?;
+		Ok((totp.generate(timestamp), totp.remaining(timestamp)))
+	}
+
+	// get the counter the next code of a counter based one-time password field will use
+	pub fn get_hotp_counter(
+		&self,
+		id: &usize,
+		field: &DbFields,
+	) -> Result<u64, OtpError> {
+		if self.get_dyn_field_kind(id, field) != Some(DynamicFieldKind::Hotp) {
+			return Err(OtpError::NotAnOtpField);
+		}
+
+		Ok(Hotp::parse(&self.get_last_by_field(id, field))?.counter)
+	}
+
+	// get a list of all dynamic fields
+	pub fn get_dyn_fields(&self, id: &usize) -> Vec<DbFields> {
+		let entry = self.get_by_id_secure(id);
+
+		entry
+			.fields
+			.iter()
+			.filter(|field| field.visible)
+			.map(|field| DbFields::Fields(field.id))
+			.collect()
+	}
+
+	// get a list of all dynamic fields
+	pub fn get_hidden_dyn_fields(&self, id: &usize) -> Vec<DbFields> {
+		let entry = self.get_by_id_secure(id);
+
+		entry
+			.fields
+			.iter()
+			.filter(|field| !field.visible)
+			.map(|field| DbFields::Fields(field.id))
 			.collect()
 	}
 
@@ -197,8 +1156,8 @@ impl Db {
 
 		match field {
 			DbFields::Id => format!("{:?}", entry.id),
-			DbFields::Title => entry.title,
-			DbFields::Url => entry.url,
+			DbFields::Title => last_value(&entry.title).to_string(),
+			DbFields::Url => last_value(&entry.url).to_string(),
 			DbFields::Username => entry.username.last().unwrap().1.clone(),
 			DbFields::Password => entry.password.last().unwrap().1.clone(),
 			DbFields::Fields(field_id) => {
@@ -218,8 +1177,12 @@ impl Db {
 
 		match field {
 			DbFields::Id => format!("{:?}", entry.id),
-			DbFields::Title => entry.title,
-			DbFields::Url => entry.url,
+			DbFields::Title => {
+				entry.title.into_iter().rev().collect::<Vec<SecureField>>()[n].1.clone()
+			}
+			DbFields::Url => {
+				entry.url.into_iter().rev().collect::<Vec<SecureField>>()[n].1.clone()
+			}
 			DbFields::Username => {
 				entry.username.into_iter().rev().collect::<Vec<SecureField>>()[n]
 					.1
@@ -251,8 +1214,12 @@ impl Db {
 
 		match field {
 			DbFields::Id => None,
-			DbFields::Title => None,
-			DbFields::Url => None,
+			DbFields::Title => {
+				Some(entry.title.into_iter().rev().collect::<im::Vector<SecureField>>())
+			}
+			DbFields::Url => {
+				Some(entry.url.into_iter().rev().collect::<im::Vector<SecureField>>())
+			}
 			DbFields::Username => Some(
 				entry.username.into_iter().rev().collect::<im::Vector<SecureField>>(),
 			),
@@ -270,92 +1237,664 @@ impl Db {
 		}
 	}
 
-	// get the date and id of a field
+	// get the date and id of a field, newest first so the id matches get_n_by_field
 	pub fn get_history_dates(
 		&self,
 		id: &usize,
 		field: &DbFields,
-	) -> Vec<(usize, u64)> {
+	) -> Vec<(usize, Option<u64>)> {
 		let entry = self.get_by_id_secure(id);
 
-		match field {
-			DbFields::Id => vec![(0, 0)],
-			DbFields::Title => vec![(0, 0)],
-			DbFields::Url => vec![(0, 0)],
-			DbFields::Username => {
-				entry.username.iter().map(|item| item.0).enumerate().collect()
+		let history = match field {
+			DbFields::Id => return vec![(0, None)],
+			DbFields::Title => entry.title,
+			DbFields::Url => entry.url,
+			DbFields::Username => entry.username,
+			DbFields::Password => entry.password,
+			DbFields::Fields(field_id) => {
+				self.get_field_by_id(&entry, field_id).value
 			}
-			DbFields::Password => {
-				entry.password.iter().map(|item| item.0).enumerate().collect()
+		};
+		history.iter().rev().map(|item| item.0).enumerate().collect()
+	}
+
+	// get the retention of the whole vault
+	pub fn get_retention(&self) -> Retention {
+		self.retention
+	}
+
+	// change the retention of the whole vault
+	pub fn set_retention(&mut self, retention: Retention) {
+		self.retention = retention;
+	}
+
+	// get the retention of a field if it overrides the vault wide one
+	pub fn get_field_retention(
+		&self,
+		id: &usize,
+		field: &DbFields,
+	) -> Option<Retention> {
+		self
+			.get_by_id_secure(id)
+			.retention
+			.iter()
+			.find(|item| item.field == *field)
+			.map(|item| item.retention)
+	}
+
+	// override the retention of a single field, None goes back to the vault wide one
+	pub fn set_field_retention(
+		&mut self,
+		id: &usize,
+		field: &DbFields,
+		retention: Option<Retention>,
+	) {
+		if let Some(entry) = self.contents.iter_mut().find(|item| item.id == *id) {
+			entry.retention.retain(|item| item.field != *field);
+			if let Some(retention) = retention {
+				entry.retention.push(FieldRetention {
+					field: *field,
+					retention,
+				});
 			}
-			DbFields::Fields(field_id) => self
-				.get_field_by_id(&entry, field_id)
-				.value
-				.iter()
-				.map(|item| item.0)
-				.enumerate()
-				.collect(),
 		}
 	}
 
+	// count the versions pruning the history right now would remove
+	pub fn count_prunable(&self, now: u64) -> usize {
+		self
+			.contents
+			.clone()
+			.iter_mut()
+			.map(|entry| entry.prune(self.retention, now).0)
+			.sum()
+	}
+
+	// remove all versions the retention settings don't keep
+	pub fn prune_history(&mut self, now: u64) -> usize {
+		let retention = self.retention;
+		let mut removed = 0;
+		let mut pruned = Vec::new();
+		for entry in self.contents.iter_mut() {
+			let (count, fields) = entry.prune(retention, now);
+			removed += count;
+			pruned.extend(fields.into_iter().map(|field| (entry.id, field)));
+		}
+
+		// undoing an edit of a pruned field could remove its last version
+		for (id, field) in pruned {
+			self.forget_edits(&id, &field);
+		}
+		// removed entries are kept for undo with their whole history
+		let prune_step = |step: &mut UndoStep| {
+			if let UndoStep::AddEntry { entry, .. }
+			| UndoStep::RemoveEntry { entry, .. } = step
+			{
+				entry.prune(retention, now);
+			}
+		};
+		self.undo_steps.iter_mut().for_each(prune_step);
+		self.redo_steps.iter_mut().for_each(prune_step);
+
+		removed
+	}
+
+	// push an older value of a field back on top of its history
+	pub fn restore_field(&mut self, id: &usize, field: &DbFields, n: usize) {
+		let mut value = self.get_n_by_field(id, field, n);
+
+		// an older counter would hand out codes that were already used
+		if self.get_dyn_field_kind(id, field) == Some(DynamicFieldKind::Hotp) {
+			if let Ok(mut hotp) = Hotp::parse(&value) {
+				hotp.counter = self
+					.get_history(id, field)
+					.unwrap_or_default()
+					.iter()
+					.filter_map(|(_, version)| Hotp::parse(version).ok())
+					.map(|version| version.counter)
+					.fold(hotp.counter, u64::max);
+				value = hotp.to_uri();
+			}
+		}
+
+		self.edit_field(*id, field, value);
+	}
+
 	// add a new entry
 	pub fn add(&mut self, title: String) -> usize {
-		let timestamp: u64 = SystemTime::now()
-			.duration_since(UNIX_EPOCH)
-			.unwrap_or(Duration::new(0, 0))
-			.as_secs();
-
-		let new_id = self
-			.contents
-			.last()
-			.unwrap_or(&DbEntry {
-				id: 1,
-				title: String::from(""),
-				url: String::from(""),
-				username: vec![(0, String::from(""))],
-				password: vec![(0, String::from(""))],
-				fields: vec![DynamicField::default()],
-			})
-			.id + 1;
+		let timestamp = get_timestamp();
 
-		self.contents.push(DbEntry {
-			id: new_id,
+		self.add_entry(NewDbEntry {
 			title,
 			url: String::from(""),
-			username: vec![(timestamp, String::from(""))],
-			password: vec![(timestamp, String::from(""))],
+			username: vec![(Some(timestamp), String::from(""))],
+			password: vec![(Some(timestamp), String::from(""))],
 			fields: vec![DynamicField {
 				id: 0,
 				title: String::from("Note"),
 				visible: true,
-				value: vec![(0, String::from(""))],
+				kind: DynamicFieldKind::Secret,
+				value: vec![(Some(timestamp), String::from(""))],
 			}],
+			group: None,
+			tags: Vec::new(),
+			kind: EntryKind::Login,
+		})
+	}
+
+	// add a new entry from a filled in form
+	pub fn add_entry(&mut self, entry: NewDbEntry) -> usize {
+		// vaults from before the counter was stored start after the highest id
+		let new_id = self
+			.next_id
+			.max(self.contents.iter().map(|item| item.id).max().unwrap_or(1) + 1);
+		self.next_id = new_id + 1;
+
+		let timestamp = get_timestamp();
+		self.contents.push(DbEntry {
+			id: new_id,
+			title: vec![(Some(timestamp), entry.title)],
+			url: vec![(Some(timestamp), entry.url)],
+			username: entry.username,
+			password: entry.password,
+			fields: entry.fields,
+			group: entry.group,
+			tags: clean_tags(entry.tags),
+			favorite: false,
+			last_used: 0,
+			attachments: Vec::new(),
+			kind: entry.kind,
+			retention: Vec::new(),
+			created: Some(timestamp),
+			modified: Some(timestamp),
+			expires: None,
+			password_max_age: None,
+			next_field_id: 0,
+			next_attachment_id: 0,
+		});
+		self.record(UndoStep::AddEntry {
+			index: self.contents.len() - 1,
+			entry: self.contents[self.contents.len() - 1].clone(),
 		});
 
 		new_id
 	}
 
+	// get the list of all groups for the sidebar tree as (id, title, parent)
+	pub fn get_groups(&self) -> Vec<(usize, &'static str, Option<usize>)> {
+		self
+			.groups
+			.iter()
+			.map(|group| {
+				(
+					group.id,
+					&*Box::leak(group.title.clone().into_boxed_str()),
+					group.parent,
+				)
+			})
+			.collect()
+	}
+
+	// get the id and name of all attachments of an entry
+	pub fn get_attachments(&self, id: &usize) -> Vec<(usize, String)> {
+		self
+			.get_by_id_secure(id)
+			.attachments
+			.iter()
+			.map(|attachment| (attachment.id, attachment.name.clone()))
+			.collect()
+	}
+
+	// get the date and size of every version of an attachment, newest first
+	pub fn get_attachment_history(
+		&self,
+		id: &usize,
+		attachment_id: &usize,
+	) -> Vec<(Option<u64>, usize)> {
+		self
+			.get_by_id_secure(id)
+			.attachments
+			.iter()
+			.find(|attachment| attachment.id == *attachment_id)
+			.map(|attachment| {
+				attachment
+					.value
+					.iter()
+					.rev()
+					.map(|value| (value.0, Attachment::size_of(value)))
+					.collect()
+			})
+			.unwrap_or_default()
+	}
+
+	// get the decoded contents of version n of an attachment, 0 is the newest
+	pub fn get_attachment_data(
+		&self,
+		id: &usize,
+		attachment_id: &usize,
+		n: usize,
+	) -> Result<Vec<u8>, AttachmentError> {
+		let entry = self.get_by_id_secure(id);
+		let value = entry
+			.attachments
+			.iter()
+			.find(|attachment| attachment.id == *attachment_id)
+			.and_then(|attachment| attachment.value.iter().rev().nth(n))
+			.ok_or(AttachmentError::NotFound)?;
+
+		Ok(general_purpose::STANDARD_NO_PAD.decode(&value.1)?)
+	}
+
+	// attach a file to an entry
+	pub fn add_attachment(
+		&mut self,
+		id: &usize,
+		name: String,
+		data: &[u8],
+	) -> Result<usize, AttachmentError> {
+		check_size(data.len())?;
+		let entry = self
+			.contents
+			.iter_mut()
+			.find(|item| item.id == *id)
+			.ok_or(AttachmentError::NotFound)?;
+
+		let new_id = entry.new_attachment_id();
+		let timestamp = get_timestamp();
+		entry.attachments.push(Attachment {
+			id: new_id,
+			name,
+			value: vec![(
+				Some(timestamp),
+				general_purpose::STANDARD_NO_PAD.encode(data),
+			)],
+		});
+		entry.modified = Some(timestamp);
+		Ok(new_id)
+	}
+
+	// store a new version of an attachment
+	pub fn update_attachment(
+		&mut self,
+		id: &usize,
+		attachment_id: &usize,
+		data: &[u8],
+	) -> Result<(), AttachmentError> {
+		check_size(data.len())?;
+		let entry = self
+			.contents
+			.iter_mut()
+			.find(|item| item.id == *id)
+			.ok_or(AttachmentError::NotFound)?;
+		let attachment = entry
+			.attachments
+			.iter_mut()
+			.find(|attachment| attachment.id == *attachment_id)
+			.ok_or(AttachmentError::NotFound)?;
+
+		let timestamp = get_timestamp();
+		attachment
+			.value
+			.push((Some(timestamp), general_purpose::STANDARD_NO_PAD.encode(data)));
+		entry.modified = Some(timestamp);
+		Ok(())
+	}
+
+	// get the built-in templates followed by the ones the user added
+	pub fn get_templates(&self) -> Vec<EntryTemplate> {
+		let mut templates = builtin_templates();
+		templates.extend(self.templates.iter().cloned());
+		templates
+	}
+
+	// find a template by its name
+	pub fn get_template(&self, title: &str) -> Option<EntryTemplate> {
+		self
+			.get_templates()
+			.into_iter()
+			.find(|template| template.title.eq_ignore_ascii_case(title.trim()))
+	}
+
+	// add a user defined template
+	pub fn add_template(
+		&mut self,
+		template: EntryTemplate,
+	) -> Result<(), TemplateError> {
+		let title = template.title.trim().to_string();
+		if title.is_empty() {
+			return Err(TemplateError::MissingTitle);
+		}
+		if self.get_template(&title).is_some() {
+			return Err(TemplateError::Duplicate(title));
+		}
+		if template.fields.iter().any(|field| field.title.trim().is_empty()) {
+			return Err(TemplateError::MissingFieldTitle);
+		}
+
+		self.templates.push(EntryTemplate {
+			title,
+			builtin: false,
+			..template
+		});
+		Ok(())
+	}
+
+	// remove a user defined template, built-in ones always stay
+	pub fn remove_template(&mut self, title: &str) -> Result<(), TemplateError> {
+		match self.get_template(title) {
+			Some(template) if template.builtin => Err(TemplateError::BuiltIn),
+			Some(template) => {
+				self.templates.retain(|item| item.title != template.title);
+				Ok(())
+			}
+			None => Err(TemplateError::NotFound),
+		}
+	}
+
+	// add a new empty entry with the fields of a template
+	pub fn add_from_template(
+		&mut self,
+		title: String,
+		template: &EntryTemplate,
+	) -> usize {
+		let timestamp = get_timestamp();
+
+		self.add_entry(NewDbEntry {
+			title,
+			url: String::from(""),
+			username: vec![(Some(timestamp), String::from(""))],
+			password: vec![(Some(timestamp), String::from(""))],
+			fields: template
+				.fields
+				.iter()
+				.enumerate()
+				.map(|(idx, field)| {
+					DynamicField::new(
+						idx + 1,
+						field.title.clone(),
+						field.kind,
+						String::from(""),
+					)
+				})
+				.collect(),
+			group: None,
+			tags: Vec::new(),
+			kind: template.kind,
+		})
+	}
+
+	// remember when an entry was last opened or copied from
+	pub fn mark_used(&mut self, id: &usize) {
+		if let Some(entry) = self.contents.iter_mut().find(|item| item.id == *id) {
+			entry.last_used = get_timestamp();
+		}
+	}
+
+	// get when an entry was last opened or copied from, none if it never was
+	pub fn get_last_used(&self, id: &usize) -> Option<u64> {
+		Some(self.get_by_id_secure(id).last_used).filter(|date| *date != 0)
+	}
+
+	// get the expiry date of an entry
+	pub fn get_expiry(&self, id: &usize) -> Option<u64> {
+		self.get_by_id_secure(id).expires
+	}
+
+	// set or clear the expiry date of an entry
+	pub fn set_expiry(&mut self, id: &usize, expires: Option<u64>) {
+		if let Some(entry) = self.contents.iter_mut().find(|item| item.id == *id) {
+			entry.expires = expires;
+		}
+	}
+
+	// get the vault wide maximum password age in days
+	pub fn get_password_max_age(&self) -> Option<u64> {
+		self.password_max_age
+	}
+
+	pub fn set_password_max_age(&mut self, days: Option<u64>) {
+		self.password_max_age = days;
+	}
+
+	// get the maximum password age of an entry, none when it uses the vault wide one
+	pub fn get_entry_password_max_age(&self, id: &usize) -> Option<u64> {
+		self.get_by_id_secure(id).password_max_age
+	}
+
+	pub fn set_entry_password_max_age(&mut self, id: &usize, days: Option<u64>) {
+		if let Some(entry) = self.contents.iter_mut().find(|item| item.id == *id) {
+			entry.password_max_age = days;
+		}
+	}
+
+	// get the age of every login password, the oldest first
+	pub fn get_password_ages(&self, now: u64) -> Vec<PasswordAge> {
+		let mut ages = self
+			.contents
+			.iter()
+			.filter(|entry| {
+				entry.kind == EntryKind::Login
+					&& !last_value(&entry.password).is_empty()
+			})
+			.map(|entry| {
+				let changed = entry.password.last().and_then(|item| item.0);
+				let days =
+					changed.map(|date| now.saturating_sub(date) / (24 * 60 * 60));
+				let max_age = entry.password_max_age.or(self.password_max_age);
+				PasswordAge {
+					id: entry.id,
+					title: last_value(&entry.title).to_string(),
+					days,
+					// a password without a date is older than any recorded one
+					is_stale: max_age
+						.is_some_and(|max| days.is_none_or(|days| days >= max)),
+				}
+			})
+			.collect::<Vec<PasswordAge>>();
+		ages.sort_by_key(|age| std::cmp::Reverse(age.days.unwrap_or(u64::MAX)));
+		ages
+	}
+
+	// get the ids of the entries whose password should be changed
+	pub fn get_stale_passwords(&self, now: u64) -> Vec<usize> {
+		self
+			.get_password_ages(now)
+			.into_iter()
+			.filter(|age| age.is_stale)
+			.map(|age| age.id)
+			.collect()
+	}
+
+	// get the entries that expired or expire soon with their title, soonest first
+	pub fn get_expiring(&self, now: u64) -> Vec<(usize, String, ExpiryStatus)> {
+		let mut expiring = self
+			.contents
+			.iter()
+			.filter_map(|entry| {
+				let expires = entry.expires?;
+				let status = ExpiryStatus::of(expires, now)?;
+				Some((expires, entry.id, last_value(&entry.title).to_string(), status))
+			})
+			.collect::<Vec<(u64, usize, String, ExpiryStatus)>>();
+		expiring.sort_by_key(|item| item.0);
+
+		expiring
+			.into_iter()
+			.map(|(_, id, title, status)| (id, title, status))
+			.collect()
+	}
+
+	// check if an entry is pinned as a favorite
+	pub fn is_favorite(&self, id: &usize) -> bool {
+		self.get_by_id_secure(id).favorite
+	}
+
+	// pin or unpin an entry as a favorite
+	pub fn toggle_favorite(&mut self, id: &usize) -> bool {
+		if let Some(entry) = self.contents.iter_mut().find(|item| item.id == *id) {
+			entry.favorite = !entry.favorite;
+		}
+		self.is_favorite(id)
+	}
+
+	// get the tags of an entry
+	pub fn get_tags(&self, id: &usize) -> Vec<String> {
+		self.get_by_id_secure(id).tags
+	}
+
+	// get every tag in use and how many entries carry it, sorted by name
+	pub fn get_all_tags(&self) -> Vec<(String, usize)> {
+		let mut tags: Vec<(String, usize)> = Vec::new();
+		for tag in self.contents.iter().flat_map(|entry| entry.tags.iter()) {
+			match tags.iter_mut().find(|item| item.0.eq_ignore_ascii_case(tag)) {
+				Some(item) => item.1 += 1,
+				None => tags.push((tag.clone(), 1)),
+			}
+		}
+		tags.sort_by_key(|item| item.0.to_lowercase());
+		tags
+	}
+
+	// add a tag to an entry
+	pub fn add_tag(&mut self, id: &usize, tag: String) -> Vec<String> {
+		if let Some(entry) = self.contents.iter_mut().find(|item| item.id == *id) {
+			let mut tags = entry.tags.clone();
+			tags.push(tag);
+			entry.tags = clean_tags(tags);
+		}
+		self.get_tags(id)
+	}
+
+	// remove a tag from an entry
+	pub fn remove_tag(&mut self, id: &usize, tag: &str) -> Vec<String> {
+		if let Some(entry) = self.contents.iter_mut().find(|item| item.id == *id) {
+			entry.tags.retain(|item| !item.eq_ignore_ascii_case(tag));
+		}
+		self.get_tags(id)
+	}
+
+	// get the group an entry is stored in
+	pub fn get_group_of(&self, id: &usize) -> Option<usize> {
+		self.get_by_id_secure(id).group
+	}
+
+	// get a group and all groups nested inside of it
+	pub fn get_group_tree(&self, id: &usize) -> Vec<usize> {
+		let mut tree = vec![*id];
+		let mut idx = 0;
+		while let Some(parent) = tree.get(idx).copied() {
+			tree.extend(
+				self
+					.groups
+					.iter()
+					.filter(|group| group.parent == Some(parent))
+					.map(|group| group.id),
+			);
+			idx += 1;
+		}
+		tree
+	}
+
+	// add a new group inside of a parent group or at the top level
+	pub fn add_group(&mut self, title: String, parent: Option<usize>) -> usize {
+		let new_id =
+			self.groups.iter().map(|group| group.id).max().unwrap_or(0) + 1;
+		self.groups.push(DbGroup {
+			id: new_id,
+			title,
+			parent,
+		});
+		new_id
+	}
+
+	// change the title of a group
+	pub fn rename_group(&mut self, id: &usize, title: String) {
+		if let Some(group) = self.groups.iter_mut().find(|group| group.id == *id) {
+			group.title = title;
+		}
+	}
+
+	// remove a group and move its entries and sub groups into its parent
+	pub fn remove_group(&mut self, id: &usize) {
+		let Some(pos) = self.groups.iter().position(|group| group.id == *id) else {
+			return;
+		};
+		let parent = self.groups.remove(pos).parent;
+
+		self
+			.groups
+			.iter_mut()
+			.filter(|group| group.parent == Some(*id))
+			.for_each(|group| group.parent = parent);
+		self
+			.contents
+			.iter_mut()
+			.filter(|entry| entry.group == Some(*id))
+			.for_each(|entry| entry.group = parent);
+	}
+
+	// move an entry into a group or to the top level
+	pub fn move_entry(&mut self, id: &usize, group: Option<usize>) {
+		if let Some(entry) = self.contents.iter_mut().find(|item| item.id == *id) {
+			entry.group = group;
+		}
+	}
+
 	// add a new field to an entry
 	pub fn add_dyn_field(
 		&mut self,
 		id: &usize,
 		title_value: String,
 		field_value: String,
+		kind: DynamicFieldKind,
 	) -> Vec<DbFields> {
+		let timestamp = get_timestamp();
+		let mut added = None;
+
 		self.contents.iter_mut().for_each(|item| {
 			if item.id == *id {
-				let id = item.fields.last().unwrap_or(&DynamicField::default()).id + 1;
-				item.fields.push(DynamicField {
+				let id = item.new_field_id();
+				let field = DynamicField {
 					id,
 					title: title_value.clone(),
 					visible: true,
-					value: vec![(0, field_value.clone())],
-				});
+					kind,
+					value: vec![(Some(timestamp), field_value.clone())],
+				};
+				item.fields.push(field.clone());
+				item.modified = Some(timestamp);
+				added = Some(field);
 			}
 		});
+
+		if let Some(field) = added {
+			self.record(UndoStep::AddField { id: *id, field });
+		}
 		self.get_dyn_fields(id)
 	}
 
+	// generate the next code of a counter based one-time password field and store the incremented counter
+	pub fn generate_hotp_code(
+		&mut self,
+		id: &usize,
+		field: &DbFields,
+	) -> Result<String, OtpError> {
+		if self.get_dyn_field_kind(id, field) != Some(DynamicFieldKind::Hotp) {
+			return Err(OtpError::NotAnOtpField);
+		}
+
+		let mut hotp = Hotp::parse(&self.get_last_by_field(id, field))?;
+		let code = hotp.generate();
+		hotp.counter += 1;
+		// undoing this would hand out the same code twice
+		self.push_value(*id, field, hotp.to_uri());
+		if let DbFields::Fields(field_id) = field {
+			self.forget_counter(id, field_id);
+		}
+
+		Ok(code)
+	}
+
 	// change the title of a dyn field
 	pub fn edit_dyn_field_title(
 		&mut self,
@@ -374,9 +1913,11 @@ impl Db {
 							id: *field_id,
 							title: String::from(""),
 							visible: true,
-							value: vec![(0, String::from(""))],
+							kind: DynamicFieldKind::Secret,
+							value: vec![(None, String::from(""))],
 						})
 						.title = title.clone();
+					item.modified = Some(get_timestamp());
 				}
 			}
 		});
@@ -399,13 +1940,79 @@ impl Db {
 							id: *field_id,
 							title: String::from(""),
 							visible,
-							value: vec![(0, String::from(""))],
+							kind: DynamicFieldKind::Secret,
+							value: vec![(None, String::from(""))],
 						})
 						.visible = visible;
 				}
 			}
 		});
 
+		if let DbFields::Fields(field_id) = field {
+			self.record(UndoStep::Visibility {
+				id: *id,
+				field_id: *field_id,
+				visible,
+			});
+		}
+		self.get_hidden_dyn_fields(id)
+	}
+
+	// swap the places of two dynamic fields of an entry
+	pub fn swap_dyn_fields(
+		&mut self,
+		id: &usize,
+		field: &DbFields,
+		other: &DbFields,
+	) -> Vec<DbFields> {
+		if let (DbFields::Fields(field_id), DbFields::Fields(other_id)) =
+			(field, other)
+		{
+			if self.swap_fields(id, (*field_id, *other_id)) {
+				self.record(UndoStep::Swap {
+					id: *id,
+					field_ids: (*field_id, *other_id),
+				});
+			}
+		}
+
+		self.get_dyn_fields(id)
+	}
+
+	fn swap_fields(&mut self, id: &usize, field_ids: (usize, usize)) -> bool {
+		let Some(entry) = self.contents.iter_mut().find(|item| item.id == *id)
+		else {
+			return false;
+		};
+		let position = |field_id: usize| {
+			entry.fields.iter().position(|field| field.id == field_id)
+		};
+
+		match (position(field_ids.0), position(field_ids.1)) {
+			(Some(pos), Some(other_pos)) => {
+				entry.fields.swap(pos, other_pos);
+				true
+			}
+			_ => false,
+		}
+	}
+
+	// delete a dynamic field with every version of it, this can't be undone
+	pub fn delete_dyn_field(
+		&mut self,
+		id: &usize,
+		field: &DbFields,
+	) -> Vec<DbFields> {
+		if let DbFields::Fields(field_id) = field {
+			if let Some(entry) = self.contents.iter_mut().find(|item| item.id == *id)
+			{
+				entry.fields.retain(|item| item.id != *field_id);
+				entry.retention.retain(|item| item.field != *field);
+				entry.modified = Some(get_timestamp());
+			}
+			self.forget_field(id, field_id);
+		}
+
 		self.get_hidden_dyn_fields(id)
 	}
 
@@ -416,53 +2023,566 @@ impl Db {
 		field: &DbFields,
 		new_content: String,
 	) {
-		let mut index: usize = 0;
-		self.contents.iter().enumerate().find(|(idx, item)| {
-			if item.id == id {
-				index = *idx;
+		if let Some(value) = self.push_value(id, field, new_content) {
+			self.record(UndoStep::Edit {
+				id,
+				field: *field,
+				value,
+			});
+		}
+	}
+
+	// push a new value on top of the history of a field without recording it for undo
+	fn push_value(
+		&mut self,
+		id: usize,
+		field: &DbFields,
+		new_content: String,
+	) -> Option<SecureField> {
+		let index = self.contents.iter().position(|item| item.id == id)?;
+		let entry = &mut self.contents[index];
+		let timestamp = get_timestamp();
+		let value = (Some(timestamp), new_content);
+
+		entry.history_mut(field)?.push(value.clone());
+		entry.modified = Some(timestamp);
+
+		Some(value)
+	}
+
+	// delete an entry with all of its history
+	pub fn remove_entry(&mut self, id: &usize) {
+		if let Some(index) = self.contents.iter().position(|item| item.id == *id) {
+			let entry = self.contents.remove(index);
+			self.record(UndoStep::RemoveEntry { index, entry });
+		}
+	}
+
+	// remember a change so it can be undone, a new change drops what could be redone
+	fn record(&mut self, step: UndoStep) {
+		self.undo_steps.push(step);
+		if self.undo_steps.len() > UNDO_LIMIT {
+			self.undo_steps.remove(0);
+		}
+		self.redo_steps.clear();
+	}
+
+	// drop a deleted field from the undo steps so its values can't come back
+	fn forget_field(&mut self, id: &usize, field_id: &usize) {
+		let keep = |step: &mut UndoStep| match step {
+			UndoStep::Edit {
+				id: step_id, field, ..
+			} => !(step_id == id && *field == DbFields::Fields(*field_id)),
+			UndoStep::AddField { id: step_id, field } => {
+				!(step_id == id && field.id == *field_id)
+			}
+			UndoStep::Visibility {
+				id: step_id,
+				field_id: step_field,
+				..
+			} => !(step_id == id && step_field == field_id),
+			UndoStep::Swap {
+				id: step_id,
+				field_ids,
+			} => {
+				!(step_id == id
+					&& (field_ids.0 == *field_id || field_ids.1 == *field_id))
+			}
+			UndoStep::AddEntry { entry, .. }
+			| UndoStep::RemoveEntry { entry, .. } => {
+				if entry.id == *id {
+					entry.fields.retain(|field| field.id != *field_id);
+				}
 				true
-			} else {
-				false
 			}
-		});
+		};
+		self.undo_steps.retain_mut(keep);
+		self.redo_steps.retain_mut(keep);
+	}
+
+	// drop the edits of a field from the undo steps once its history changed
+	// without them
+	fn forget_edits(&mut self, id: &usize, field: &DbFields) {
+		let keep = |step: &UndoStep| {
+			!matches!(step, UndoStep::Edit {
+				id: step_id,
+				field: step_field,
+				..
+			} if step_id == id && step_field == field)
+		};
+		self.undo_steps.retain(keep);
+		self.redo_steps.retain(keep);
+	}
 
-		if let Some(entry) = self.contents.get_mut(index) {
-			let timestamp: u64 = SystemTime::now()
-				.duration_since(UNIX_EPOCH)
-				.unwrap_or(Duration::new(0, 0))
-				.as_secs();
+	// keep undo from taking the counter of a one-time password field back to a
+	// value it already handed out a code for
+	fn forget_counter(&mut self, id: &usize, field_id: &usize) {
+		let Some(current) = self
+			.contents
+			.iter()
+			.find(|item| item.id == *id)
+			.and_then(|entry| entry.fields.iter().find(|field| field.id == *field_id))
+			.map(|field| field.value.clone())
+		else {
+			return;
+		};
 
-			match field {
-				DbFields::Id => {
-					panic!("Can't change the ID of an entry");
-				}
-				DbFields::Title => {
-					entry.title = new_content;
+		let keep = |step: &mut UndoStep| match step {
+			UndoStep::Edit {
+				id: step_id, field, ..
+			} => !(step_id == id && *field == DbFields::Fields(*field_id)),
+			UndoStep::AddField { id: step_id, field } => {
+				!(step_id == id && field.id == *field_id)
+			}
+			UndoStep::AddEntry { entry, .. }
+			| UndoStep::RemoveEntry { entry, .. } => {
+				if entry.id == *id {
+					entry
+						.fields
+						.iter_mut()
+						.filter(|field| field.id == *field_id)
+						.for_each(|field| field.value = current.clone());
 				}
-				DbFields::Url => {
-					entry.url = new_content;
+				true
+			}
+			UndoStep::Visibility { .. } | UndoStep::Swap { .. } => true,
+		};
+		self.undo_steps.retain_mut(keep);
+		self.redo_steps.retain_mut(keep);
+	}
+
+	// revert a step or apply it again and return the id of the entry it changed
+	fn apply_step(&mut self, step: &mut UndoStep, undo: bool) -> usize {
+		let is_added = matches!(step, UndoStep::AddEntry { .. });
+		match step {
+			UndoStep::Edit { id, field, value } => {
+				if let Some(history) = self
+					.contents
+					.iter_mut()
+					.find(|item| item.id == *id)
+					.and_then(|entry| entry.history_mut(field))
+				{
+					// a field never loses its last version
+					if !undo {
+						history.push(value.clone());
+					} else if history.len() > 1 && history.last() == Some(value) {
+						history.pop();
+					}
 				}
-				DbFields::Username => {
-					entry.username.push((timestamp, new_content));
+				*id
+			}
+			UndoStep::AddField { id, field } => {
+				if let Some(entry) =
+					self.contents.iter_mut().find(|item| item.id == *id)
+				{
+					if undo {
+						entry.fields.retain(|item| item.id != field.id);
+					} else {
+						entry.fields.push(field.clone());
+					}
 				}
-				DbFields::Password => {
-					entry.password.push((timestamp, new_content));
+				*id
+			}
+			UndoStep::Visibility {
+				id,
+				field_id,
+				visible,
+			} => {
+				if let Some(field) =
+					self.contents.iter_mut().find(|item| item.id == *id).and_then(
+						|entry| entry.fields.iter_mut().find(|field| field.id == *field_id),
+					) {
+					field.visible = if undo { !*visible } else { *visible };
 				}
-				DbFields::Fields(field_id) => {
-					entry
-						.fields
-						.iter_mut()
-						.find(|field| field.id == *field_id)
-						.unwrap_or(&mut DynamicField {
-							id: *field_id,
-							title: String::from(""),
-							visible: true,
-							value: vec![(0, String::from(""))],
-						})
-						.value
-						.push((timestamp, new_content));
+				*id
+			}
+			UndoStep::Swap { id, field_ids } => {
+				self.swap_fields(id, *field_ids);
+				*id
+			}
+			UndoStep::AddEntry { index, entry }
+			| UndoStep::RemoveEntry { index, entry } => {
+				if is_added == undo {
+					// keep the changes made since the step was recorded for redo
+					if let Some(position) =
+						self.contents.iter().position(|item| item.id == entry.id)
+					{
+						*index = position;
+						*entry = self.contents.remove(position);
+					}
+				} else {
+					let index = (*index).min(self.contents.len());
+					self.contents.insert(index, entry.clone());
 				}
+				entry.id
+			}
+		}
+	}
+
+	// revert the last change of this session and return the id of the entry it changed
+	pub fn undo(&mut self) -> Option<usize> {
+		let mut step = self.undo_steps.pop()?;
+		let id = self.apply_step(&mut step, true);
+		self.redo_steps.push(step);
+		Some(id)
+	}
+
+	// apply the last undone change again and return the id of the entry it changed
+	pub fn redo(&mut self) -> Option<usize> {
+		let mut step = self.redo_steps.pop()?;
+		let id = self.apply_step(&mut step, false);
+		self.undo_steps.push(step);
+		Some(id)
+	}
+
+	// check if an entry still exists, undo can remove the one that is shown
+	pub fn has_entry(&self, id: &usize) -> bool {
+		self.contents.iter().any(|item| item.id == *id)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const DAY: u64 = 24 * 60 * 60;
+	const HOTP_URI: &str =
+		"otpauth://hotp/x?secret=GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ&counter=0";
+
+	fn add_hotp_field(db: &mut Db, id: &usize) -> DbFields {
+		*db
+			.add_dyn_field(
+				id,
+				String::from("Code"),
+				String::from(HOTP_URI),
+				DynamicFieldKind::Hotp,
+			)
+			.last()
+			.unwrap()
+	}
+
+	fn history(dates: &[Option<u64>]) -> Vec<SecureField> {
+		dates
+			.iter()
+			.enumerate()
+			.map(|(idx, date)| (*date, idx.to_string()))
+			.collect()
+	}
+
+	fn values(history: &[SecureField]) -> Vec<&str> {
+		history.iter().map(|(_, value)| value.as_str()).collect()
+	}
+
+	#[test]
+	fn undo_never_winds_a_hotp_counter_back() {
+		let mut db = Db::default();
+		let field = add_hotp_field(&mut db, &1);
+		db.edit_field(1, &field, HOTP_URI.replace("counter=0", "counter=5"));
+
+		assert_eq!(db.generate_hotp_code(&1, &field).unwrap(), "254676");
+		assert_eq!(db.get_hotp_counter(&1, &field).unwrap(), 6);
+		// neither the edit nor adding the field can be undone anymore
+		assert_eq!(db.undo(), None);
+		assert_eq!(db.get_hotp_counter(&1, &field).unwrap(), 6);
+	}
+
+	#[test]
+	fn removed_entries_keep_the_current_hotp_counter() {
+		let mut db = Db::default();
+		let field = add_hotp_field(&mut db, &1);
+		db.generate_hotp_code(&1, &field).unwrap();
+		db.remove_entry(&1);
+
+		db.undo();
+		assert_eq!(db.get_hotp_counter(&1, &field).unwrap(), 1);
+	}
+
+	#[test]
+	fn removed_ids_are_not_reused() {
+		let mut db = Db::default();
+		let first = db.add(String::from("Mail"));
+		db.remove_entry(&first);
+		let second = db.add(String::from("Chat"));
+		assert_ne!(first, second);
+		assert!(second > first);
+
+		// ids also move on when the removal is undone and redone
+		db.undo();
+		db.undo();
+		db.redo();
+		db.redo();
+		assert!(!db.has_entry(&first));
+		assert_eq!(db.get_last_by_field(&second, &DbFields::Title), "Chat");
+	}
+
+	#[test]
+	fn ids_of_old_vaults_start_after_the_highest_one() {
+		let mut db = Db {
+			next_id: 0,
+			..Default::default()
+		};
+		assert_eq!(db.add(String::from("Mail")), 2);
+
+		let mut empty = Db {
+			contents: Vec::new(),
+			next_id: 0,
+			..Default::default()
+		};
+		assert_eq!(empty.add(String::from("Mail")), 2);
+		assert_eq!(empty.add(String::from("Chat")), 3);
+	}
+
+	#[test]
+	fn search_matches_urls_usernames_and_fields() {
+		let db = Db::default();
+		let search = |query: &str, include_secrets| {
+			db.search(query, include_secrets, None, &[], SortOrder::Created)
+		};
+
+		assert_eq!(search("australia", false), [(1, DbFields::Url)]);
+		assert_eq!(search("user:dom", false), [(1, DbFields::Username)]);
+		assert_eq!(search("field:notes", false), [(1, DbFields::Fields(0))]);
+		// secret values are only searched when asked to
+		assert!(search("deets", false).is_empty());
+		assert_eq!(search("deets", true), [(1, DbFields::Fields(0))]);
+		assert!(search("totally_secure", false).is_empty());
+		assert_eq!(search("totally_secure", true), [(1, DbFields::Password)]);
+		assert!(search("url:dom", false).is_empty());
+	}
+
+	#[test]
+	fn retention_keeps_the_newest_versions() {
+		let now = 100 * DAY;
+		let old = history(&[Some(DAY), Some(2 * DAY), Some(3 * DAY), Some(now)]);
+
+		let keep = |max_versions, max_age_days| {
+			Retention {
+				max_versions,
+				max_age_days,
+			}
+			.keep(&old, now)
+		};
+		assert_eq!(values(&keep(None, None)), ["0", "1", "2", "3"]);
+		assert_eq!(values(&keep(Some(2), None)), ["2", "3"]);
+		assert_eq!(values(&keep(Some(1), None)), ["3"]);
+		assert_eq!(values(&keep(None, Some(98))), ["1", "2", "3"]);
+		assert_eq!(values(&keep(Some(1), Some(98))), ["3"]);
+		// the current value stays even if it is too old
+		assert_eq!(values(&keep(None, Some(0))), ["3"]);
+	}
+
+	#[test]
+	fn retention_drops_undated_versions_by_age_only() {
+		let now = 100 * DAY;
+		let old = history(&[None, Some(now - DAY), None]);
+
+		let by_age = Retention {
+			max_versions: None,
+			max_age_days: Some(7),
+		};
+		assert_eq!(values(&by_age.keep(&old, now)), ["1", "2"]);
+		let by_count = Retention {
+			max_versions: Some(3),
+			max_age_days: None,
+		};
+		assert_eq!(values(&by_count.keep(&old, now)), ["0", "1", "2"]);
+	}
+
+	#[test]
+	fn epoch_dates_load_as_unknown() {
+		let stored =
+			toml::to_string(&Db::default()).unwrap().replace("1702851212", "0");
+		let db = toml::from_str::<Db>(&stored).unwrap();
+		assert_eq!(db.get_history_dates(&1, &DbFields::Password), [(0, None)]);
+
+		let saved = toml::to_string(&db).unwrap();
+		assert!(!saved.contains("1702851212"));
+		let reloaded = toml::from_str::<Db>(&saved).unwrap();
+		assert_eq!(
+			reloaded.get_history_dates(&1, &DbFields::Password),
+			[(0, None)]
+		);
+	}
+
+	#[test]
+	fn undo_and_redo_an_edit() {
+		let mut db = Db::default();
+		db.edit_field(1, &DbFields::Title, String::from("Credit union"));
+		assert_eq!(db.get_last_by_field(&1, &DbFields::Title), "Credit union");
+
+		assert_eq!(db.undo(), Some(1));
+		assert_eq!(db.get_last_by_field(&1, &DbFields::Title), "Bank");
+		assert_eq!(db.undo(), None);
+
+		assert_eq!(db.redo(), Some(1));
+		assert_eq!(db.get_last_by_field(&1, &DbFields::Title), "Credit union");
+		assert_eq!(db.redo(), None);
+	}
+
+	#[test]
+	fn new_changes_drop_redo() {
+		let mut db = Db::default();
+		db.edit_field(1, &DbFields::Url, String::from("first"));
+		db.undo();
+		db.edit_field(1, &DbFields::Url, String::from("second"));
+		assert_eq!(db.redo(), None);
+		assert_eq!(db.get_history(&1, &DbFields::Url).unwrap().len(), 2);
+	}
+
+	#[test]
+	fn undo_and_redo_removing_an_entry() {
+		let mut db = Db::default();
+		db.remove_entry(&1);
+		assert!(!db.has_entry(&1));
+
+		assert_eq!(db.undo(), Some(1));
+		assert!(db.has_entry(&1));
+		assert_eq!(db.get_last_by_field(&1, &DbFields::Title), "Bank");
+
+		assert_eq!(db.redo(), Some(1));
+		assert!(!db.has_entry(&1));
+	}
+
+	#[test]
+	fn undo_after_prune_keeps_the_last_version() {
+		let mut db = Db::default();
+		db.edit_field(1, &DbFields::Password, String::from("new_password"));
+		db.set_retention(Retention {
+			max_versions: Some(1),
+			max_age_days: None,
+		});
+		assert_eq!(db.prune_history(get_timestamp()), 1);
+
+		db.undo();
+		let history = db.get_history(&1, &DbFields::Password).unwrap();
+		assert_eq!(history.len(), 1);
+		assert_eq!(db.get_last_by_field(&1, &DbFields::Password), "new_password");
+	}
+
+	#[test]
+	fn undo_never_removes_the_last_version() {
+		let mut db = Db::default();
+		db.edit_field(1, &DbFields::Username, String::from("dom"));
+		if let Some(entry) = db.contents.iter_mut().find(|item| item.id == 1) {
+			entry.username.remove(0);
+		}
+
+		db.undo();
+		assert_eq!(db.get_last_by_field(&1, &DbFields::Username), "dom");
+	}
+
+	#[test]
+	fn redo_keeps_changes_made_after_adding_an_entry() {
+		let mut db = Db::default();
+		let id = db.add(String::from("Mail"));
+		db.add_tag(&id, String::from("work"));
+		db.toggle_favorite(&id);
+
+		assert_eq!(db.undo(), Some(id));
+		assert!(!db.has_entry(&id));
+		assert_eq!(db.redo(), Some(id));
+		assert_eq!(db.get_tags(&id), [String::from("work")]);
+		assert!(db.is_favorite(&id));
+	}
+
+	#[test]
+	fn edits_of_missing_entries_are_ignored() {
+		let mut db = Db::default();
+		db.edit_field(7, &DbFields::Title, String::from("Mail"));
+		db.edit_field(1, &DbFields::Fields(7), String::from("Mail"));
+		assert_eq!(db.get_last_by_field(&1, &DbFields::Title), "Bank");
+		assert_eq!(db.undo(), None);
+	}
+
+	#[test]
+	fn restoring_a_hotp_field_keeps_the_counter() {
+		let mut db = Db::default();
+		let field = add_hotp_field(&mut db, &1);
+		let other_secret =
+			HOTP_URI.replace("GEZDGNBVGY3TQOJQGEZD", "MFRGGZDFMZTWQ2LKNNWG");
+		db.edit_field(1, &field, other_secret);
+		for _ in 0..3 {
+			db.generate_hotp_code(&1, &field).unwrap();
+		}
+
+		let versions = db.get_history(&1, &field).unwrap().len();
+		db.restore_field(&1, &field, versions - 1);
+		assert_eq!(db.get_hotp_counter(&1, &field).unwrap(), 3);
+		assert_eq!(db.generate_hotp_code(&1, &field).unwrap(), "969429");
+	}
+
+	#[test]
+	fn deleted_field_ids_are_not_reused() {
+		let mut db = Db::default();
+		let field = *db
+			.add_dyn_field(
+				&1,
+				String::from("Pin"),
+				String::from("1234"),
+				DynamicFieldKind::Secret,
+			)
+			.last()
+			.unwrap();
+		db.delete_dyn_field(&1, &field);
+
+		let new_field = *db
+			.add_dyn_field(
+				&1,
+				String::from("Pin"),
+				String::from("5678"),
+				DynamicFieldKind::Secret,
+			)
+			.last()
+			.unwrap();
+		assert_ne!(field, new_field);
+		assert_eq!(db.get_history(&1, &new_field).unwrap().len(), 1);
+	}
+
+	#[test]
+	fn attachment_ids_are_not_reused() {
+		let mut db = Db::default();
+		let first = db.add_attachment(&1, String::from("a.txt"), b"a").unwrap();
+		if let Some(entry) = db.contents.iter_mut().find(|item| item.id == 1) {
+			entry.attachments.clear();
+		}
+		let second = db.add_attachment(&1, String::from("b.txt"), b"b").unwrap();
+		assert_ne!(first, second);
+	}
+
+	#[test]
+	fn sorts_by_creation_date() {
+		let mut db = Db::default();
+		let first = db.add(String::from("First"));
+		let second = db.add(String::from("Second"));
+		for (id, created) in [(1, None), (first, Some(200)), (second, Some(100))] {
+			if let Some(entry) = db.contents.iter_mut().find(|item| item.id == id) {
+				entry.created = created;
 			}
 		}
+		let order = db
+			.search("", false, None, &[], SortOrder::Created)
+			.into_iter()
+			.map(|(id, _)| id)
+			.collect::<Vec<usize>>();
+		assert_eq!(order, [first, second, 1]);
+	}
+
+	#[test]
+	fn entries_expire_after_their_expiry_day() {
+		let expires = parse_date("2026-10-18").unwrap();
+		assert_eq!(format_date(expires), "2026-10-18");
+
+		let start_of_day = expires - DAY + 1;
+		assert_eq!(format_date(start_of_day), "2026-10-18");
+		assert_eq!(
+			ExpiryStatus::of(expires, start_of_day),
+			Some(ExpiryStatus::ExpiringSoon)
+		);
+		assert_eq!(
+			ExpiryStatus::of(expires, expires + 1),
+			Some(ExpiryStatus::Expired)
+		);
+		assert_eq!(ExpiryStatus::of(expires, expires - 30 * DAY), None);
+		assert_eq!(parse_date("2026-13-01"), None);
 	}
 }
@@ -0,0 +1,229 @@
+// inputs longer than this are compared as a whole to keep the lcs table small
+const MAX_DIFF_ITEMS: usize = 2000;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Change {
+	Same,
+	Added,
+	Removed,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Span {
+	pub change: Change,
+	pub text: String,
+}
+
+// one line of a side-by-side diff, an empty side has no line at that position
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DiffRow {
+	pub left: Vec<Span>,
+	pub right: Vec<Span>,
+}
+
+// the longest common subsequence of two lists as a list of changes
+fn lcs<T: PartialEq + Clone>(old: &[T], new: &[T]) -> Vec<(Change, T)> {
+	if old.len() > MAX_DIFF_ITEMS || new.len() > MAX_DIFF_ITEMS {
+		return old
+			.iter()
+			.map(|item| (Change::Removed, item.clone()))
+			.chain(new.iter().map(|item| (Change::Added, item.clone())))
+			.collect();
+	}
+
+	let mut table = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+	for i in (0..old.len()).rev() {
+		for j in (0..new.len()).rev() {
+			table[i][j] = if old[i] == new[j] {
+				table[i + 1][j + 1] + 1
+			} else {
+				table[i + 1][j].max(table[i][j + 1])
+			};
+		}
+	}
+
+	let mut changes = Vec::new();
+	let (mut i, mut j) = (0, 0);
+	while i < old.len() && j < new.len() {
+		if old[i] == new[j] {
+			changes.push((Change::Same, old[i].clone()));
+			i += 1;
+			j += 1;
+		} else if table[i + 1][j] >= table[i][j + 1] {
+			changes.push((Change::Removed, old[i].clone()));
+			i += 1;
+		} else {
+			changes.push((Change::Added, new[j].clone()));
+			j += 1;
+		}
+	}
+	changes.extend(old[i..].iter().map(|item| (Change::Removed, item.clone())));
+	changes.extend(new[j..].iter().map(|item| (Change::Added, item.clone())));
+	changes
+}
+
+// join neighbouring characters with the same change into spans
+fn to_spans(changes: impl Iterator<Item = (Change, char)>) -> Vec<Span> {
+	let mut spans: Vec<Span> = Vec::new();
+	for (change, c) in changes {
+		match spans.last_mut() {
+			Some(span) if span.change == change => span.text.push(c),
+			_ => spans.push(Span {
+				change,
+				text: c.to_string(),
+			}),
+		}
+	}
+	spans
+}
+
+// compare two lines character by character, returning the old and the new side
+pub fn diff_chars(old: &str, new: &str) -> (Vec<Span>, Vec<Span>) {
+	let changes = lcs(
+		&old.chars().collect::<Vec<char>>(),
+		&new.chars().collect::<Vec<char>>(),
+	);
+
+	(
+		to_spans(
+			changes.iter().copied().filter(|(change, _)| *change != Change::Added),
+		),
+		to_spans(
+			changes.iter().copied().filter(|(change, _)| *change != Change::Removed),
+		),
+	)
+}
+
+fn whole_line(change: Change, text: &str) -> Vec<Span> {
+	vec![Span {
+		change,
+		text: String::from(text),
+	}]
+}
+
+// pair up a block of removed and added lines so edited lines sit next to each other
+fn paired_rows(removed: &[&str], added: &[&str]) -> Vec<DiffRow> {
+	(0..removed.len().max(added.len()))
+		.map(|idx| match (removed.get(idx), added.get(idx)) {
+			(Some(old), Some(new)) => {
+				let (left, right) = diff_chars(old, new);
+				DiffRow { left, right }
+			}
+			(Some(old), None) => DiffRow {
+				left: whole_line(Change::Removed, old),
+				right: Vec::new(),
+			},
+			(None, new) => DiffRow {
+				left: Vec::new(),
+				right: new
+					.map(|new| whole_line(Change::Added, new))
+					.unwrap_or_default(),
+			},
+		})
+		.collect()
+}
+
+// compare two values line by line and highlight the changed characters of changed lines
+pub fn side_by_side(old: &str, new: &str) -> Vec<DiffRow> {
+	let changes = lcs(
+		&old.lines().collect::<Vec<&str>>(),
+		&new.lines().collect::<Vec<&str>>(),
+	);
+
+	let mut rows = Vec::new();
+	let mut removed: Vec<&str> = Vec::new();
+	let mut added: Vec<&str> = Vec::new();
+
+	for (change, line) in changes {
+		match change {
+			Change::Removed => removed.push(line),
+			Change::Added => added.push(line),
+			Change::Same => {
+				rows.extend(paired_rows(&removed, &added));
+				removed.clear();
+				added.clear();
+				rows.push(DiffRow {
+					left: whole_line(Change::Same, line),
+					right: whole_line(Change::Same, line),
+				});
+			}
+		}
+	}
+	rows.extend(paired_rows(&removed, &added));
+
+	rows
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn span(change: Change, text: &str) -> Span {
+		Span {
+			change,
+			text: String::from(text),
+		}
+	}
+
+	#[test]
+	fn diffs_characters() {
+		let (left, right) = diff_chars("password1", "passw0rd12");
+		assert_eq!(
+			left,
+			vec![
+				span(Change::Same, "passw"),
+				span(Change::Removed, "o"),
+				span(Change::Same, "rd1"),
+			]
+		);
+		assert_eq!(
+			right,
+			vec![
+				span(Change::Same, "passw"),
+				span(Change::Added, "0"),
+				span(Change::Same, "rd1"),
+				span(Change::Added, "2"),
+			]
+		);
+	}
+
+	#[test]
+	fn diffs_identical_and_empty_values() {
+		assert_eq!(
+			diff_chars("same", "same"),
+			(vec![span(Change::Same, "same")], vec![span(Change::Same, "same")])
+		);
+		assert_eq!(
+			diff_chars("", "new"),
+			(vec![], vec![span(Change::Added, "new")])
+		);
+		assert!(side_by_side("", "").is_empty());
+	}
+
+	#[test]
+	fn pairs_changed_lines() {
+		let rows = side_by_side("one\ntwo\nthree", "one\nTwo\nthree\nfour");
+		assert_eq!(rows.len(), 4);
+		assert_eq!(rows[0].left, vec![span(Change::Same, "one")]);
+		assert_eq!(
+			rows[1].left,
+			vec![span(Change::Removed, "t"), span(Change::Same, "wo")]
+		);
+		assert_eq!(
+			rows[1].right,
+			vec![span(Change::Added, "T"), span(Change::Same, "wo")]
+		);
+		assert_eq!(rows[2].right, vec![span(Change::Same, "three")]);
+		assert!(rows[3].left.is_empty());
+		assert_eq!(rows[3].right, vec![span(Change::Added, "four")]);
+	}
+
+	#[test]
+	fn compares_long_values_as_a_whole() {
+		let old = "a".repeat(MAX_DIFF_ITEMS + 1);
+		let new = format!("{}b", old);
+		let (left, right) = diff_chars(&old, &new);
+		assert_eq!(left, vec![span(Change::Removed, &old)]);
+		assert_eq!(right, vec![span(Change::Added, &new)]);
+	}
+}
@@ -5,6 +5,7 @@ use aes_gcm_siv::{
 };
 use argon2::Argon2;
 use base64::{engine::general_purpose, Engine as _};
+use crypto_common::rand_core::RngCore;
 use std::str::from_utf8;
 
 #[derive(thiserror::Error, Debug)]
@@ -65,3 +66,24 @@ pub fn encrypt_vault(
 		general_purpose::STANDARD_NO_PAD.encode(payload).to_string();
 	Ok(b64_payload)
 }
+
+pub const DEFAULT_PASSWORD_LENGTH: usize = 20;
+
+const PASSWORD_CHARS: &[u8] =
+	b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789!@#$%^&*()-_=+[]{};:,.<>?";
+
+pub fn generate_password(length: usize) -> String {
+	let mut rng = OsRng;
+	// rejection sampling so every character is equally likely
+	let limit = u32::MAX - (u32::MAX % PASSWORD_CHARS.len() as u32);
+
+	(0..length)
+		.map(|_| loop {
+			let value = rng.next_u32();
+			if value < limit {
+				break PASSWORD_CHARS[(value % PASSWORD_CHARS.len() as u32) as usize]
+					as char;
+			}
+		})
+		.collect()
+}
@@ -0,0 +1,14 @@
+//! The vault library: open a vault config file, unlock the encrypted
+//! database, query and edit its entries and save it back to disk.
+//!
+//! The floem GUI and the command-line interface in `main.rs` are both built
+//! on top of this crate.
+
+pub mod attachment;
+pub mod config;
+pub mod db;
+pub mod diff;
+pub mod encryption;
+pub mod otp;
+pub mod search;
+pub mod template;
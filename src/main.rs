@@ -15,25 +15,34 @@ use floem::{
 	window::WindowConfig,
 	Application,
 };
+use vault::attachment;
 
-pub mod config;
-pub mod db;
-mod encryption;
+mod cli;
 
 mod ui {
 	pub mod app_view;
 	pub mod colors;
+	pub mod database_view;
 	pub mod details {
+		pub mod attachments;
 		pub mod button_slots;
 		pub mod detail_view;
 		pub mod dyn_field_title_form;
+		pub mod entry_info;
 		pub mod hidden_fields;
 		pub mod list_item;
+		pub mod move_buttons;
+		pub mod new_entry;
 		pub mod new_field;
+		pub mod note_item;
+		pub mod tag_list;
 	}
+	pub mod diff_view;
 	pub mod history_view;
+	pub mod password_age_view;
 	pub mod password_view;
 	pub mod settings_view;
+	pub mod templates_view;
 	pub mod window_management;
 	pub mod primitives {
 		pub mod button;
@@ -47,9 +56,20 @@ use crate::ui::app_view::app_view;
 use crate::ui::password_view::password_view;
 
 fn main() {
+	let args: Vec<String> = std::env::args().skip(1).collect();
+	if !args.is_empty() {
+		std::process::exit(cli::run(args));
+	}
+
 	let password = create_rw_signal(String::from(""));
 	let error = create_rw_signal(String::from(""));
-	let config = Arc::new(RwLock::new(config::Config::new()));
+	let config = match cli::load_config() {
+		Ok(config) => Arc::new(RwLock::new(config)),
+		Err(error) => {
+			eprintln!("Error: {:#}", error);
+			std::process::exit(1);
+		}
+	};
 
 	let view = container(
 		dyn_container(
@@ -71,6 +91,7 @@ fn main() {
 				} else {
 					let timeout = config.read().general.read().db_timeout;
 					exec_after(Duration::from_secs_f64(timeout), move |_| {
+						let _ = attachment::remove_temp_files();
 						password.set(String::from(""));
 						error.set(String::from(""));
 					});
@@ -123,4 +144,6 @@ fn main() {
 			),
 		)
 		.run();
+
+	let _ = attachment::remove_temp_files();
 }
@@ -0,0 +1,204 @@
+const SCORE_MATCH: i64 = 10;
+const SCORE_CONSECUTIVE: i64 = 8;
+const SCORE_PREFIX: i64 = 25;
+const SCORE_WORD_BOUNDARY: i64 = 12;
+const PENALTY_GAP: i64 = 1;
+const SCORE_RECENCY: i64 = 30;
+const RECENCY_HALF_LIFE_DAYS: i64 = 7;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum QueryTerm {
+	Text(String),
+	Url(String),
+	User(String),
+	Field { name: String, value: Option<String> },
+	Tag(String),
+}
+
+// split a query into terms, supporting "quoted phrases" and the operators
+// url:, user:, field:name=value and tag:
+pub fn parse_query(query: &str) -> Vec<QueryTerm> {
+	let mut words = Vec::new();
+	let mut word = String::new();
+	let mut in_quotes = false;
+
+	for char in query.chars() {
+		match char {
+			'"' => in_quotes = !in_quotes,
+			c if c.is_whitespace() && !in_quotes => {
+				if !word.is_empty() {
+					words.push(std::mem::take(&mut word));
+				}
+			}
+			c => word.push(c),
+		}
+	}
+	if !word.is_empty() {
+		words.push(word);
+	}
+
+	words
+		.into_iter()
+		.filter_map(|word| {
+			let term = match word.split_once(':') {
+				Some(("url", value)) => QueryTerm::Url(value.to_string()),
+				Some(("user", value)) => QueryTerm::User(value.to_string()),
+				Some(("tag", value)) => QueryTerm::Tag(value.to_string()),
+				Some(("field", value)) => match value.split_once('=') {
+					Some((name, value)) => QueryTerm::Field {
+						name: name.to_string(),
+						value: Some(value.to_string()),
+					},
+					None => QueryTerm::Field {
+						name: value.to_string(),
+						value: None,
+					},
+				},
+				_ => QueryTerm::Text(word.clone()),
+			};
+
+			match &term {
+				QueryTerm::Text(value)
+				| QueryTerm::Url(value)
+				| QueryTerm::User(value)
+				| QueryTerm::Tag(value)
+					if value.is_empty() =>
+				{
+					None
+				}
+				QueryTerm::Field { name, .. } if name.is_empty() => None,
+				_ => Some(term),
+			}
+		})
+		.collect()
+}
+
+fn is_word_boundary(previous: Option<char>, current: char) -> bool {
+	match previous {
+		None => true,
+		Some(previous) => {
+			!previous.is_alphanumeric()
+				|| (previous.is_lowercase() && current.is_uppercase())
+		}
+	}
+}
+
+// score how well the needle matches the haystack as a case-insensitive
+// subsequence, None means the needle is not contained at all
+pub fn fuzzy_score(needle: &str, haystack: &str) -> Option<i64> {
+	if needle.is_empty() {
+		return Some(0);
+	}
+
+	let haystack_chars = haystack.chars().collect::<Vec<char>>();
+	let mut needle_chars = needle.chars().flat_map(char::to_lowercase).peekable();
+	let mut score = 0;
+	let mut last_match: Option<usize> = None;
+
+	for (idx, char) in haystack_chars.iter().enumerate() {
+		let Some(wanted) = needle_chars.peek() else {
+			break;
+		};
+
+		if char.to_lowercase().eq(std::iter::once(*wanted)) {
+			score += SCORE_MATCH;
+			if idx == 0 {
+				score += SCORE_PREFIX;
+			}
+			if is_word_boundary(idx.checked_sub(1).map(|i| haystack_chars[i]), *char)
+			{
+				score += SCORE_WORD_BOUNDARY;
+			}
+			match last_match {
+				Some(last) if last + 1 == idx => score += SCORE_CONSECUTIVE,
+				Some(last) => score -= PENALTY_GAP * (idx - last - 1) as i64,
+				None => {}
+			}
+
+			last_match = Some(idx);
+			needle_chars.next();
+		}
+	}
+
+	if needle_chars.peek().is_some() {
+		None
+	} else {
+		Some(score)
+	}
+}
+
+// boost recently edited entries, the boost halves after a week and keeps shrinking
+pub fn recency_score(last_edit: u64, now: u64) -> i64 {
+	let age_days = (now.saturating_sub(last_edit) / 86_400) as i64;
+	SCORE_RECENCY * RECENCY_HALF_LIFE_DAYS / (RECENCY_HALF_LIFE_DAYS + age_days)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn parses_operators() {
+		let text = |value: &str| QueryTerm::Text(value.to_string());
+		let field = |name: &str, value: Option<&str>| QueryTerm::Field {
+			name: name.to_string(),
+			value: value.map(str::to_string),
+		};
+		let cases = [
+			("", vec![]),
+			("bank", vec![text("bank")]),
+			("  bank   mail ", vec![text("bank"), text("mail")]),
+			("\"bank of australia\"", vec![text("bank of australia")]),
+			("url:example.com", vec![QueryTerm::Url("example.com".into())]),
+			("user:dom", vec![QueryTerm::User("dom".into())]),
+			("tag:work", vec![QueryTerm::Tag("work".into())]),
+			("field:pin=1234", vec![field("pin", Some("1234"))]),
+			("field:pin", vec![field("pin", None)]),
+			("field:pin=", vec![field("pin", Some(""))]),
+			("\"tag:two words\"", vec![QueryTerm::Tag("two words".into())]),
+			("other:value", vec![text("other:value")]),
+			("url: user: tag: field:", vec![]),
+			(
+				"mail user:dom tag:work",
+				vec![
+					text("mail"),
+					QueryTerm::User("dom".into()),
+					QueryTerm::Tag("work".into()),
+				],
+			),
+		];
+		for (query, terms) in cases {
+			assert_eq!(parse_query(query), terms, "{:?}", query);
+		}
+	}
+
+	#[test]
+	fn fuzzy_matches_subsequences() {
+		assert_eq!(fuzzy_score("", "anything"), Some(0));
+		assert!(fuzzy_score("bnk", "Bank").is_some());
+		assert!(fuzzy_score("BANK", "bank").is_some());
+		assert_eq!(fuzzy_score("knab", "Bank"), None);
+		assert_eq!(fuzzy_score("banks", "Bank"), None);
+	}
+
+	#[test]
+	fn fuzzy_prefers_tight_matches() {
+		let score = |needle, haystack| fuzzy_score(needle, haystack).unwrap();
+		// prefix over the middle of a word
+		assert!(score("ban", "Bank") > score("ank", "Bank"));
+		// consecutive over spread out
+		assert!(score("mail", "Mail") > score("mail", "My alias list"));
+		// word boundaries over the middle of a word
+		assert!(score("gm", "Google Mail") > score("gm", "Programs"));
+		assert!(score("gm", "GoogleMail") > score("gm", "Programs"));
+	}
+
+	#[test]
+	fn recency_halves_after_a_week() {
+		let now = 100 * 86_400;
+		assert_eq!(recency_score(now, now), SCORE_RECENCY);
+		assert_eq!(recency_score(now - 7 * 86_400, now), SCORE_RECENCY / 2);
+		assert!(recency_score(0, now) < recency_score(now - 86_400, now));
+		assert_eq!(recency_score(now + 86_400, now), SCORE_RECENCY);
+	}
+}
@@ -0,0 +1,118 @@
+use serde::{Deserialize, Serialize};
+
+use crate::db::{DynamicFieldKind, EntryKind};
+
+#[derive(thiserror::Error, Debug)]
+pub enum TemplateError {
+	#[error("The template needs a name.")]
+	MissingTitle,
+	#[error("A template named \"{0}\" already exists.")]
+	Duplicate(String),
+	#[error("Every field of the template needs a name.")]
+	MissingFieldTitle,
+	#[error("Built-in templates can't be removed.")]
+	BuiltIn,
+	#[error("The template could not be found.")]
+	NotFound,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct TemplateField {
+	pub title: String,
+	pub kind: DynamicFieldKind,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+pub struct EntryTemplate {
+	pub title: String,
+	pub kind: EntryKind,
+	pub fields: Vec<TemplateField>,
+	#[serde(skip)]
+	pub builtin: bool,
+}
+
+fn builtin(
+	title: &str,
+	kind: EntryKind,
+	fields: &[(&str, DynamicFieldKind)],
+) -> EntryTemplate {
+	EntryTemplate {
+		title: String::from(title),
+		kind,
+		fields: fields
+			.iter()
+			.map(|(title, kind)| TemplateField {
+				title: String::from(*title),
+				kind: *kind,
+			})
+			.collect(),
+		builtin: true,
+	}
+}
+
+// the templates that ship with the app, the first one is the plain login
+pub fn builtin_templates() -> Vec<EntryTemplate> {
+	vec![
+		builtin("Login", EntryKind::Login, &[("Note", DynamicFieldKind::Secret)]),
+		builtin(
+			"Credit card",
+			EntryKind::SecureNote,
+			&[
+				("Cardholder", DynamicFieldKind::Text),
+				("Number", DynamicFieldKind::Secret),
+				("Expires", DynamicFieldKind::Text),
+				("CVV", DynamicFieldKind::Secret),
+				("PIN", DynamicFieldKind::Secret),
+			],
+		),
+		builtin(
+			"SSH key",
+			EntryKind::SecureNote,
+			&[
+				("Host", DynamicFieldKind::Text),
+				("User", DynamicFieldKind::Text),
+				("Private key", DynamicFieldKind::Note),
+				("Public key", DynamicFieldKind::Note),
+				("Passphrase", DynamicFieldKind::Secret),
+			],
+		),
+		builtin(
+			"Database",
+			EntryKind::Login,
+			&[
+				("Host", DynamicFieldKind::Text),
+				("Port", DynamicFieldKind::Number),
+				("Database", DynamicFieldKind::Text),
+			],
+		),
+		builtin(
+			"Wi-Fi",
+			EntryKind::SecureNote,
+			&[
+				("Network", DynamicFieldKind::Text),
+				("Password", DynamicFieldKind::Secret),
+				("Security", DynamicFieldKind::Text),
+			],
+		),
+		builtin(
+			"API key",
+			EntryKind::SecureNote,
+			&[
+				("Endpoint", DynamicFieldKind::Url),
+				("Key ID", DynamicFieldKind::Text),
+				("Secret", DynamicFieldKind::Secret),
+			],
+		),
+		builtin(
+			"Identity",
+			EntryKind::SecureNote,
+			&[
+				("Full name", DynamicFieldKind::Text),
+				("Email", DynamicFieldKind::Email),
+				("Phone", DynamicFieldKind::Phone),
+				("Birthday", DynamicFieldKind::Date),
+				("Address", DynamicFieldKind::Note),
+			],
+		),
+	]
+}
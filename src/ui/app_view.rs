@@ -1,47 +1,203 @@
 use floem::{
 	event::{Event, EventListener},
-	keyboard::{KeyCode, PhysicalKey},
+	keyboard::{KeyCode, ModifiersState, PhysicalKey},
 	kurbo::Size,
 	peniko::Color,
-	reactive::{create_rw_signal, create_signal},
-	style::{CursorStyle, Display, Position},
+	reactive::{create_effect, create_rw_signal, create_signal, RwSignal},
+	style::{CursorStyle, Display, FlexWrap, Position},
 	view::View,
 	views::{
 		container, dyn_container, h_stack, label, scroll, svg, v_stack,
 		virtual_stack, Decorators, VirtualDirection, VirtualItemSize,
 	},
-	EventPropagation,
+	Clipboard, EventPropagation,
 };
 
-use crate::{
+use std::collections::{HashMap, HashSet};
+
+use vault::{
 	config::Config,
-	ui::{
-		colors::*,
-		details::detail_view::detail_view,
-		primitives::{
-			button::{icon_button, IconButton},
-			input_field::input_field,
-			styles,
-			tooltip::{tooltip_view, TooltipSignals},
-		},
-		settings_view::settings_view,
-		window_management::{opening_window, WindowSpec},
+	db::{get_timestamp, DbFields, ExpiryStatus, SortOrder},
+};
+
+use crate::ui::{
+	colors::*,
+	details::{
+		detail_view::detail_view,
+		new_entry::{new_entry, NewEntry},
+	},
+	primitives::{
+		button::{icon_button, text_button, IconButton},
+		input_field::{focused_input, input_field},
+		styles,
+		tooltip::{tooltip_view, TooltipSignals},
 	},
+	settings_view::settings_view,
+	window_management::{opening_window, WindowSpec},
 };
 
 const SIDEBAR_WIDTH: f64 = 140.0;
 const SEARCHBAR_HEIGHT: f64 = 30.0;
+const GROUP_INDENT: f64 = 12.0;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+enum SidebarRow {
+	Group {
+		id: usize,
+		title: &'static str,
+		depth: usize,
+	},
+	Entry {
+		id: usize,
+		title: &'static str,
+		depth: usize,
+		pos: usize,
+		favorite: bool,
+		stale: bool,
+	},
+}
+
+struct SidebarTree<'a> {
+	list: &'a im::Vector<(usize, &'static str, usize)>,
+	groups: &'a [(usize, &'static str, Option<usize>)],
+	entry_groups: &'a HashMap<usize, Option<usize>>,
+	favorites: &'a HashSet<usize>,
+	stale: &'a HashSet<usize>,
+	collapsed: &'a HashSet<usize>,
+	filled: HashSet<usize>,
+	show_empty: bool,
+	rows: Vec<SidebarRow>,
+	entries: Vec<usize>,
+}
+
+impl SidebarTree<'_> {
+	// add the groups and then the entries of one level of the tree
+	fn push_level(&mut self, parent: Option<usize>, depth: usize) {
+		let groups = self.groups;
+		let list = self.list;
+		let entry_groups = self.entry_groups;
+
+		for (id, title, _) in groups.iter().filter(|group| group.2 == parent) {
+			if !self.show_empty && !self.filled.contains(id) {
+				continue;
+			}
+			self.rows.push(SidebarRow::Group {
+				id: *id,
+				title,
+				depth,
+			});
+			// search results are always shown expanded
+			if !self.show_empty || !self.collapsed.contains(id) {
+				self.push_level(Some(*id), depth + 1);
+			}
+		}
+
+		for (id, title, _) in list
+			.iter()
+			.filter(|item| entry_groups.get(&item.0).copied().flatten() == parent)
+		{
+			self.rows.push(SidebarRow::Entry {
+				id: *id,
+				title,
+				depth,
+				pos: self.entries.len(),
+				favorite: self.favorites.contains(id),
+				stale: self.stale.contains(id),
+			});
+			self.entries.push(*id);
+		}
+	}
+}
+
+// flatten the groups and the listed entries into the rows of the sidebar and
+// the order entries are navigated in with the keyboard
+fn build_tree(
+	list: &im::Vector<(usize, &'static str, usize)>,
+	groups: &[(usize, &'static str, Option<usize>)],
+	entry_groups: &HashMap<usize, Option<usize>>,
+	favorites: &HashSet<usize>,
+	stale: &HashSet<usize>,
+	collapsed: &HashSet<usize>,
+	show_empty: bool,
+) -> (im::Vector<(usize, SidebarRow)>, Vec<usize>) {
+	// groups with a listed entry somewhere inside of them
+	let mut filled = HashSet::new();
+	for (id, _, _) in list {
+		let mut group = entry_groups.get(id).copied().flatten();
+		while let Some(group_id) = group {
+			if !filled.insert(group_id) {
+				break;
+			}
+			group =
+				groups.iter().find(|item| item.0 == group_id).and_then(|item| item.2);
+		}
+	}
+
+	let mut tree = SidebarTree {
+		list,
+		groups,
+		entry_groups,
+		favorites,
+		stale,
+		collapsed,
+		filled,
+		show_empty,
+		rows: Vec::new(),
+		entries: Vec::new(),
+	};
+	tree.push_level(None, 0);
+
+	(tree.rows.into_iter().enumerate().collect(), tree.entries)
+}
 
 pub fn app_view(config: Config) -> impl View {
 	let db = config.db.read().get_list();
-	let db_backup = config.db.read().get_list();
-	let config_search = config.clone();
+	let config_filter = config.clone();
+	let config_keys = config.clone();
+	let config_tree = config.clone();
+	let config_add_group = config.clone();
+	let config_remove_group = config.clone();
+	let config_rows = config.clone();
+	let config_sort = config.clone();
+	let config_open = config.clone();
+	let config_settings = config.clone();
 
 	let sidebar_width = create_rw_signal(SIDEBAR_WIDTH);
 	let is_sidebar_dragging = create_rw_signal(false);
 	let (list, set_list) = create_signal(db.clone());
-	let (active_tab, set_active_tab) = create_signal(db[0].0);
+	// an empty vault has nothing to show so it starts with the new entry form
+	let (active_tab, set_active_tab) =
+		create_signal(db.front().map(|item| item.0).unwrap_or_default());
 	let search_text = create_rw_signal(String::from(""));
+	let search_secrets = create_rw_signal(false);
+	let matched_fields = create_rw_signal(HashMap::<usize, String>::new());
+	let selected = create_rw_signal(None::<usize>);
+	let creating = create_rw_signal(db.is_empty());
+	let groups = create_rw_signal(config.db.read().get_groups());
+	let collapsed = create_rw_signal(HashSet::<usize>::new());
+	let active_group = create_rw_signal(None::<usize>);
+	let tree = create_rw_signal(im::Vector::<(usize, SidebarRow)>::new());
+	let visible_entries = create_rw_signal(Vec::<usize>::new());
+	let dragging = create_rw_signal(None::<usize>);
+	let drop_target = create_rw_signal(None::<Option<usize>>);
+	let renaming = create_rw_signal(None::<usize>);
+	let rename_text = create_rw_signal(String::from(""));
+	let all_tags = create_rw_signal(config.db.read().get_all_tags());
+	let tag_filter = create_rw_signal(Vec::<String>::new());
+	let expiring =
+		create_rw_signal(config.db.read().get_expiring(get_timestamp()));
+	let expiry_filter = create_rw_signal(false);
+	// credentials past their expiry date are listed once when the vault opens
+	let expired_notice = create_rw_signal(
+		expiring
+			.get_untracked()
+			.iter()
+			.filter(|item| item.2 == ExpiryStatus::Expired)
+			.map(|item| item.1.clone())
+			.collect::<Vec<String>>(),
+	);
+	let sort_order = create_rw_signal(config.general.read().sort_order);
+	let refresh_list = create_rw_signal(());
 	let sidebar_scrolled = create_rw_signal(false);
 	let main_scroll_to = create_rw_signal(0.0);
 
@@ -50,6 +206,100 @@ pub fn app_view(config: Config) -> impl View {
 
 	let clear_icon = include_str!("./icons/clear.svg");
 	let settings_icon = include_str!("./icons/settings.svg");
+	let see_icon = include_str!("./icons/see.svg");
+	let hide_icon = include_str!("./icons/hide.svg");
+	let add_icon = include_str!("./icons/add.svg");
+	let minus_icon = include_str!("./icons/minus.svg");
+	let expand_icon = include_str!("./icons/expand.svg");
+	let contract_icon = include_str!("./icons/contract.svg");
+
+	create_effect(move |_| {
+		let query = search_text.get();
+		let tags = tag_filter.get();
+		let only_expiring = expiry_filter.get();
+		groups.track();
+		refresh_list.track();
+		// an empty query lists every entry, otherwise only search the selected group
+		let group = if query.is_empty() && tags.is_empty() && !only_expiring {
+			None
+		} else {
+			active_group.get()
+		};
+		let mut results = config_filter.db.read().search(
+			&query,
+			search_secrets.get(),
+			group,
+			&tags,
+			sort_order.get(),
+		);
+
+		let expiring_now = config_filter.db.read().get_expiring(get_timestamp());
+		if only_expiring {
+			results.retain(|(id, _)| expiring_now.iter().any(|item| item.0 == *id));
+		}
+		expiring.set(expiring_now);
+		let full_list = config_filter.db.read().get_list();
+
+		let mut matches = HashMap::new();
+		for (id, field) in &results {
+			match field {
+				DbFields::Title => {}
+				DbFields::Fields(_) => {
+					matches.insert(
+						*id,
+						config_filter.db.read().get_name_of_dyn_field(id, field),
+					);
+				}
+				other => {
+					matches.insert(*id, other.to_string());
+				}
+			}
+		}
+
+		set_list.set(
+			results
+				.iter()
+				.filter_map(|(id, _)| full_list.iter().find(|item| item.0 == *id))
+				.enumerate()
+				.map(|(idx, item)| (item.0, item.1, idx))
+				.collect(),
+		);
+		matched_fields.set(matches);
+	});
+
+	create_effect(move |_| {
+		let list = list.get();
+		let entry_groups = list
+			.iter()
+			.map(|item| (item.0, config_tree.db.read().get_group_of(&item.0)))
+			.collect::<HashMap<usize, Option<usize>>>();
+		let favorites = list
+			.iter()
+			.filter(|item| config_tree.db.read().is_favorite(&item.0))
+			.map(|item| item.0)
+			.collect::<HashSet<usize>>();
+		let stale = config_tree
+			.db
+			.read()
+			.get_stale_passwords(get_timestamp())
+			.into_iter()
+			.collect::<HashSet<usize>>();
+
+		let (rows, entries) = build_tree(
+			&list,
+			&groups.get(),
+			&entry_groups,
+			&favorites,
+			&stale,
+			&collapsed.get(),
+			search_text.get().is_empty()
+				&& tag_filter.get().is_empty()
+				&& !expiry_filter.get(),
+		);
+		tree.set(rows);
+		visible_entries.set(entries);
+		selected.set(None);
+	});
 
 	let search_text_input_view = input_field(search_text);
 	let search_text_input_view_id = search_text_input_view.id();
@@ -73,21 +323,30 @@ pub fn app_view(config: Config) -> impl View {
 				})
 				.placeholder("Press enter to create a new entry")
 				.keyboard_navigatable()
-				.on_event(EventListener::KeyDown, move |_| {
-					set_list.update(
-						|list: &mut im::Vector<(usize, &'static str, usize)>| {
-							*list = db
-								.iter()
-								.cloned()
-								.filter(|item| {
-									item
-										.1
-										.to_lowercase()
-										.contains(&search_text.get().to_lowercase())
-								})
-								.collect::<im::Vector<_>>();
-						},
-					);
+				.on_event(EventListener::KeyDown, move |event| {
+					let key = match event {
+						Event::KeyDown(k) => k.key.physical_key,
+						_ => PhysicalKey::Code(KeyCode::F35),
+					};
+					let len = visible_entries.get().len();
+
+					if key == PhysicalKey::Code(KeyCode::ArrowDown) && len > 0 {
+						selected.set(Some(match selected.get() {
+							Some(idx) => (idx + 1).min(len - 1),
+							None => 0,
+						}));
+					}
+
+					if key == PhysicalKey::Code(KeyCode::ArrowUp) {
+						selected.set(match selected.get() {
+							Some(0) | None => None,
+							Some(idx) => Some(idx - 1),
+						});
+					}
+
+					if key == PhysicalKey::Code(KeyCode::Escape) {
+						selected.set(None);
+					}
 					EventPropagation::Continue
 				})
 				.on_event(EventListener::KeyUp, move |event| {
@@ -97,15 +356,19 @@ pub fn app_view(config: Config) -> impl View {
 					};
 
 					if key == PhysicalKey::Code(KeyCode::Enter) {
+						if let Some(id) = selected
+							.get()
+							.and_then(|idx| visible_entries.get().get(idx).copied())
 						{
-							config_search.clone().db.write().add(search_text.get());
+							config_open.db.write().mark_used(&id);
+							creating.set(false);
+							set_active_tab.set(id);
+							main_scroll_to.set(0.0);
+							return EventPropagation::Continue;
 						}
-						// TODO: Create a form view of the detail view before writing to the db
 
-						let new_list = config_search.db.read().get_list();
-						set_list.set(new_list.clone());
-						search_text.set(String::from(""));
-						set_active_tab.set(new_list[0].0);
+						creating.set(true);
+						main_scroll_to.set(0.0);
 					}
 					EventPropagation::Continue
 				}),
@@ -116,12 +379,6 @@ pub fn app_view(config: Config) -> impl View {
 		)
 		.on_click_stop(move |_| {
 			search_text.set(String::from(""));
-			set_list.update(|list: &mut im::Vector<(usize, &'static str, usize)>| {
-				*list = db_backup
-					.iter()
-					.map(|entries| (entries.0, entries.1, entries.2))
-					.collect();
-			});
 		})
 		.keyboard_navigatable()
 		.style(move |s| {
@@ -138,6 +395,18 @@ pub fn app_view(config: Config) -> impl View {
 				.hover(|s| s.cursor(CursorStyle::Pointer))
 				.apply_if(!search_text.get().is_empty(), |s| s.display(Display::Flex))
 		}),
+		icon_button(
+			IconButton {
+				icon: String::from(see_icon),
+				icon2: Some(String::from(hide_icon)),
+				bubble: None::<RwSignal<Vec<u8>>>,
+				tooltip: String::from("Include secret fields in search"),
+				tooltip2: Some(String::from("Exclude secret fields from search")),
+				switch: Some(search_secrets),
+				tooltip_signals,
+			},
+			|_| {},
+		),
 		// TODO: add log-out button for manual logging out
 		icon_button(
 			IconButton::<u8> {
@@ -148,7 +417,7 @@ pub fn app_view(config: Config) -> impl View {
 			},
 			move |_| {
 				opening_window(
-					settings_view,
+					move || settings_view(config_settings.clone()),
 					WindowSpec {
 						id: String::from("settings-window"),
 						title: String::from("Vault Settings"),
@@ -167,75 +436,471 @@ pub fn app_view(config: Config) -> impl View {
 			.gap(3.0, 0.0)
 	});
 
-	let sidebar = scroll({
+	let sidebar_header = h_stack((
+		label(move || match active_group.get() {
+			Some(id) => groups
+				.get()
+				.iter()
+				.find(|group| group.0 == id)
+				.map(|group| format!("All entries › {}", group.1))
+				.unwrap_or_else(|| String::from("All entries")),
+			None => String::from("All entries"),
+		})
+		.on_click_stop(move |_| {
+			active_group.set(None);
+		})
+		.on_event(EventListener::PointerEnter, move |_| {
+			if dragging.get().is_some() {
+				drop_target.set(Some(None));
+			}
+			EventPropagation::Continue
+		})
+		.on_event(EventListener::PointerLeave, move |_| {
+			drop_target.set(None);
+			EventPropagation::Continue
+		})
+		.style(move |s| {
+			s.text_ellipsis()
+				.flex_grow(1.0)
+				.flex_basis(0)
+				.min_width(0)
+				.padding_left(10.0)
+				.padding_top(6.0)
+				.font_size(12.0)
+				.color(C_TEXT_TOP)
+				.cursor(CursorStyle::Pointer)
+				.apply_if(
+					dragging.get().is_some() && drop_target.get() == Some(None),
+					|s| s.border(1).border_color(C_FOCUS),
+				)
+		}),
+		text_button(
+			move || sort_order.get().to_string(),
+			String::from("Change the order of the entries"),
+			tooltip_signals,
+			move |_| {
+				let pos = SortOrder::ALL
+					.iter()
+					.position(|item| *item == sort_order.get())
+					.unwrap_or(0);
+				let next = SortOrder::ALL[(pos + 1) % SortOrder::ALL.len()];
+				config_sort.general.write().sort_order = next;
+				let _ = config_sort.save_general();
+				sort_order.set(next);
+			},
+		),
+		icon_button(
+			IconButton::<u8> {
+				icon: String::from(add_icon),
+				tooltip: String::from("Add a new group"),
+				tooltip_signals,
+				..IconButton::default()
+			},
+			move |_| {
+				let parent = active_group.get();
+				let id = config_add_group
+					.db
+					.write()
+					.add_group(String::from("New group"), parent);
+				if let Some(parent) = parent {
+					collapsed.update(|collapsed| {
+						collapsed.remove(&parent);
+					});
+				}
+				groups.set(config_add_group.db.read().get_groups());
+				rename_text.set(String::from("New group"));
+				renaming.set(Some(id));
+			},
+		),
+		container(icon_button(
+			IconButton::<u8> {
+				icon: String::from(minus_icon),
+				tooltip: String::from(
+					"Remove the selected group, its entries move to the parent group",
+				),
+				tooltip_signals,
+				..IconButton::default()
+			},
+			move |_| {
+				if let Some(id) = active_group.get() {
+					config_remove_group.db.write().remove_group(&id);
+					active_group.set(None);
+					groups.set(config_remove_group.db.read().get_groups());
+				}
+			},
+		))
+		.style(move |s| {
+			s.display(Display::None)
+				.apply_if(active_group.get().is_some(), |s| s.display(Display::Flex))
+		}),
+	))
+	.style(move |s| {
+		s.width(sidebar_width.get() - 1.0)
+			.items_start()
+			.border_bottom(1.0)
+			.border_color(C_BG_SIDE_BORDER)
+			.background(C_BG_SIDE)
+	});
+
+	// drop filters for tags that are no longer used by any entry
+	create_effect(move |_| {
+		let tags = all_tags.get();
+		let filter = tag_filter.get_untracked();
+		if filter.iter().any(|tag| !tags.iter().any(|item| item.0 == *tag)) {
+			tag_filter.set(
+				filter
+					.into_iter()
+					.filter(|tag| tags.iter().any(|item| item.0 == *tag))
+					.collect(),
+			);
+		}
+	});
+
+	let tag_bar = floem::views::list(
+		move || all_tags.get(),
+		move |tag| tag.clone(),
+		move |(tag, count)| {
+			let tag_toggle = tag.clone();
+			let tag_style = tag.clone();
+
+			label(move || format!("{} {}", tag, count))
+				.keyboard_navigatable()
+				.on_click_stop(move |_| {
+					tag_filter.update(|filter| {
+						if filter.contains(&tag_toggle) {
+							filter.retain(|item| *item != tag_toggle);
+						} else {
+							filter.push(tag_toggle.clone());
+						}
+					});
+				})
+				.style(move |s| {
+					s.font_size(10.0)
+						.padding(1)
+						.padding_left(5)
+						.padding_right(5)
+						.margin_right(3)
+						.margin_bottom(3)
+						.border_radius(8)
+						.border(1)
+						.border_color(C_BG_SIDE_BORDER)
+						.color(C_TEXT_SIDE)
+						.background(C_BG_SIDE_SELECTED)
+						.hover(|s| s.cursor(CursorStyle::Pointer))
+						.focus_visible(|s| s.border_color(C_FOCUS))
+						.apply_if(tag_filter.get().contains(&tag_style), |s| {
+							s.background(C_FOCUS).color(C_BG_MAIN).border_color(C_FOCUS)
+						})
+				})
+		},
+	)
+	.style(move |s| {
+		s.flex_row()
+			.flex_wrap(FlexWrap::Wrap)
+			.width(sidebar_width.get() - 1.0)
+			.padding(5)
+			.padding_bottom(2)
+			.border_bottom(1.0)
+			.border_color(C_BG_SIDE_BORDER)
+			.apply_if(all_tags.get().is_empty(), |s| s.display(Display::None))
+	});
+
+	let expiry_bar = container(
+		label(move || {
+			let expired = expiring
+				.get()
+				.iter()
+				.filter(|item| item.2 == ExpiryStatus::Expired)
+				.count();
+			format!(
+				"Expiring soon / expired {} / {}",
+				expiring.get().len() - expired,
+				expired
+			)
+		})
+		.keyboard_navigatable()
+		.on_click_stop(move |_| {
+			expiry_filter.set(!expiry_filter.get());
+		})
+		.style(move |s| {
+			s.font_size(10.0)
+				.padding(1)
+				.padding_left(5)
+				.padding_right(5)
+				.border_radius(8)
+				.border(1)
+				.border_color(C_BG_SIDE_BORDER)
+				.color(C_TEXT_SIDE)
+				.background(C_BG_SIDE_SELECTED)
+				.hover(|s| s.cursor(CursorStyle::Pointer))
+				.focus_visible(|s| s.border_color(C_FOCUS))
+				.apply_if(expiry_filter.get(), |s| {
+					s.background(C_FOCUS).color(C_BG_MAIN).border_color(C_FOCUS)
+				})
+		}),
+	)
+	.style(move |s| {
+		s.width(sidebar_width.get() - 1.0)
+			.padding(5)
+			.border_bottom(1.0)
+			.border_color(C_BG_SIDE_BORDER)
+			.apply_if(expiring.get().is_empty() && !expiry_filter.get(), |s| {
+				s.display(Display::None)
+			})
+	});
+
+	let sidebar = scroll(v_stack((
+		sidebar_header,
+		expiry_bar,
+		tag_bar,
 		virtual_stack(
 			VirtualDirection::Vertical,
 			VirtualItemSize::Fixed(Box::new(|| 22.0)),
-			move || list.get(),
+			move || tree.get(),
 			move |item| *item,
-			move |item| {
-				container(
-					label(move || item.1)
-						.style(|s| s.font_size(12.0).color(C_TEXT_SIDE))
-						.keyboard_navigatable()
-						.on_text_overflow(move |is_overflown| {
-							let mut labels = overflow_labels.get();
-							if is_overflown {
-								labels.push(item.0);
+			move |(idx, row)| {
+				let (id, title, depth, pos, favorite, stale, is_group) = match row {
+					SidebarRow::Group { id, title, depth } => {
+						(id, title, depth, None, false, false, true)
+					}
+					SidebarRow::Entry {
+						id,
+						title,
+						depth,
+						pos,
+						favorite,
+						stale,
+					} => (id, title, depth, Some(pos), favorite, stale, false),
+				};
+				let indent = 10.0 + depth as f64 * GROUP_INDENT;
+				let config_drag = config_rows.clone();
+				let config_rename = config_rows.clone();
+				let config_click = config_rows.clone();
+
+				let rename_input = input_field(rename_text);
+				let rename_input_id = rename_input.id();
+				create_effect(move |_| {
+					if is_group && renaming.get() == Some(id) {
+						rename_input_id.request_focus();
+					}
+				});
+
+				container(h_stack((
+					h_stack((
+						svg(move || {
+							if collapsed.get().contains(&id) {
+								String::from(expand_icon)
 							} else {
-								labels.retain(|i| *i != item.0);
+								String::from(contract_icon)
 							}
-							overflow_labels.set(labels);
 						})
-						.on_event(EventListener::PointerEnter, move |_event| {
-							let labels = overflow_labels.get();
-							if labels.contains(&item.0) {
-								tooltip_signals.show(String::from(item.1));
+						.style(|s| s.height(10).width(10).margin_right(4)),
+						label(move || title).style(|s| s.font_size(12.0)),
+					))
+					.keyboard_navigatable()
+					.on_click_stop(move |_| {
+						active_group.set(Some(id));
+						collapsed.update(|collapsed| {
+							if !collapsed.remove(&id) {
+								collapsed.insert(id);
+							}
+						});
+					})
+					.on_event(EventListener::DoubleClick, move |_| {
+						rename_text.set(String::from(title));
+						renaming.set(Some(id));
+						EventPropagation::Stop
+					})
+					.on_event(EventListener::PointerEnter, move |_| {
+						if dragging.get().is_some() {
+							drop_target.set(Some(Some(id)));
+						}
+						EventPropagation::Continue
+					})
+					.on_event(EventListener::PointerLeave, move |_| {
+						drop_target.set(None);
+						EventPropagation::Continue
+					})
+					.style(move |s| {
+						s.items_center()
+							.padding(10.0)
+							.padding_left(indent)
+							.padding_top(3.0)
+							.padding_bottom(3.0)
+							.width(sidebar_width.get())
+							.border_bottom(1.0)
+							.border_color(C_BG_SIDE_BORDER)
+							.color(C_TEXT_TOP)
+							.background(C_BG_SIDE)
+							.display(Display::None)
+							.apply_if(is_group && renaming.get() != Some(id), |s| {
+								s.display(Display::Flex)
+							})
+							.apply_if(active_group.get() == Some(id), |s| {
+								s.background(C_BG_SIDE_SELECTED)
+							})
+							.apply_if(
+								dragging.get().is_some() && drop_target.get() == Some(Some(id)),
+								|s| s.border(1).border_color(C_FOCUS),
+							)
+							.focus_visible(|s| s.border(1).border_color(C_FOCUS))
+							.hover(|s| s.cursor(CursorStyle::Pointer))
+					}),
+					rename_input
+						.on_event(EventListener::KeyDown, move |event| {
+							let key = match event {
+								Event::KeyDown(k) => k.key.physical_key,
+								_ => PhysicalKey::Code(KeyCode::F35),
+							};
+
+							if key == PhysicalKey::Code(KeyCode::Enter) {
+								if !rename_text.get().is_empty() {
+									config_rename.db.write().rename_group(&id, rename_text.get());
+									groups.set(config_rename.db.read().get_groups());
+								}
+								renaming.set(None);
+							}
+
+							if key == PhysicalKey::Code(KeyCode::Escape) {
+								renaming.set(None);
 							}
 							EventPropagation::Continue
 						})
-						.on_event(EventListener::PointerLeave, move |_| {
-							tooltip_signals.hide();
-							EventPropagation::Continue
-						})
-						.on_click_stop(move |_| {
-							set_active_tab.set(item.0);
-							main_scroll_to.set(0.0);
-						})
 						.style(move |s| {
-							s.text_ellipsis()
-								.padding(10.0)
-								.padding_top(3.0)
-								.padding_bottom(3.0)
-								.width(sidebar_width.get())
-								.items_start()
-								.border_bottom(1.0)
-								.border_color(C_BG_SIDE_BORDER)
-								.color(C_TEXT_SIDE)
-								.focus_visible(|s| s.border(1).border_color(C_FOCUS))
-								.background(if let 0 = item.2 % 2 {
-									C_BG_SIDE
-								} else {
-									C_BG_SIDE_SELECTED.with_alpha_factor(0.2)
-								})
-								.apply_if(item.0 == active_tab.get(), |s| {
-									s.background(C_BG_SIDE_SELECTED)
-								})
-								.hover(|s| {
-									s.background(C_BG_SIDE_SELECTED.with_alpha_factor(0.6))
-										.apply_if(item.0 == active_tab.get(), |s| {
-											s.background(C_BG_SIDE_SELECTED)
-										})
-										.cursor(CursorStyle::Pointer)
+							s.font_size(12.0)
+								.margin_left(indent - 5.0)
+								.width(sidebar_width.get() - indent - 5.0)
+								.display(Display::None)
+								.apply_if(is_group && renaming.get() == Some(id), |s| {
+									s.display(Display::Flex)
 								})
 						}),
-				)
+					label(move || {
+						if favorite {
+							format!("★ {}", title)
+						} else {
+							String::from(title)
+						}
+					})
+					.style(|s| s.font_size(12.0).color(C_TEXT_SIDE))
+					.keyboard_navigatable()
+					.on_text_overflow(move |is_overflown| {
+						let mut labels = overflow_labels.get();
+						if is_overflown {
+							labels.push(id);
+						} else {
+							labels.retain(|i| *i != id);
+						}
+						overflow_labels.set(labels);
+					})
+					.on_event(EventListener::PointerEnter, move |_event| {
+						let labels = overflow_labels.get();
+						if labels.contains(&id) {
+							tooltip_signals.show(String::from(title));
+						}
+						EventPropagation::Continue
+					})
+					.on_event(EventListener::PointerLeave, move |_| {
+						tooltip_signals.hide();
+						EventPropagation::Continue
+					})
+					.on_click_stop(move |_| {
+						config_click.db.write().mark_used(&id);
+						creating.set(false);
+						set_active_tab.set(id);
+						main_scroll_to.set(0.0);
+					})
+					.draggable()
+					.dragging_style(|s| {
+						s.background(C_BG_SIDE_SELECTED).border(1).border_color(C_FOCUS)
+					})
+					.on_event(EventListener::DragStart, move |_| {
+						dragging.set(Some(id));
+						EventPropagation::Continue
+					})
+					.on_event(EventListener::DragEnd, move |_| {
+						if let Some(group) = drop_target.get() {
+							config_drag.db.write().move_entry(&id, group);
+							groups.set(config_drag.db.read().get_groups());
+						}
+						dragging.set(None);
+						drop_target.set(None);
+						EventPropagation::Continue
+					})
+					.style(move |s| {
+						s.text_ellipsis()
+							.padding(10.0)
+							.padding_left(indent)
+							.padding_top(3.0)
+							.padding_bottom(3.0)
+							.width(sidebar_width.get())
+							.items_start()
+							.border_bottom(1.0)
+							.border_color(C_BG_SIDE_BORDER)
+							.color(C_TEXT_SIDE)
+							.focus_visible(|s| s.border(1).border_color(C_FOCUS))
+							.background(if let 0 = idx % 2 {
+								C_BG_SIDE
+							} else {
+								C_BG_SIDE_SELECTED.with_alpha_factor(0.2)
+							})
+							.apply_if(is_group, |s| s.display(Display::None))
+							.apply_if(id == active_tab.get(), |s| {
+								s.background(C_BG_SIDE_SELECTED)
+							})
+							.apply_if(pos.is_some() && selected.get() == pos, |s| {
+								s.border(1).border_color(C_FOCUS)
+							})
+							.hover(|s| {
+								s.background(C_BG_SIDE_SELECTED.with_alpha_factor(0.6))
+									.apply_if(id == active_tab.get(), |s| {
+										s.background(C_BG_SIDE_SELECTED)
+									})
+									.cursor(CursorStyle::Pointer)
+							})
+					}),
+					label(move || {
+						matched_fields.get().get(&id).cloned().unwrap_or_default()
+					})
+					.style(move |s| {
+						s.position(Position::Absolute)
+							.inset_top(4)
+							.inset_right(4)
+							.padding_left(3)
+							.padding_right(3)
+							.font_size(9.0)
+							.border_radius(2)
+							.color(C_BG_MAIN)
+							.background(C_FOCUS)
+							.display(Display::None)
+							.apply_if(
+								!is_group && matched_fields.get().contains_key(&id),
+								|s| s.display(Display::Flex),
+							)
+					}),
+					// the search match badge takes the place while searching
+					label(|| "rotate").style(move |s| {
+						s.position(Position::Absolute)
+							.inset_top(4)
+							.inset_right(4)
+							.padding_left(3)
+							.padding_right(3)
+							.font_size(9.0)
+							.border_radius(2)
+							.color(C_BG_MAIN)
+							.background(C_ERROR)
+							.display(Display::None)
+							.apply_if(stale && !matched_fields.get().contains_key(&id), |s| {
+								s.display(Display::Flex)
+							})
+					}),
+				)))
 			},
 		)
 		.style(move |s| {
 			s.flex_col().width(sidebar_width.get() - 1.0).background(C_BG_SIDE)
-		})
-	})
+		}),
+	)))
 	.on_scroll(move |x| {
 		tooltip_signals.tooltip_visible.set(false);
 		if x.y0 > 0.0 {
@@ -310,16 +975,39 @@ pub fn app_view(config: Config) -> impl View {
 
 	let main_window = scroll(
 		dyn_container(
-			move || active_tab.get(),
-			move |id| {
-				Box::new(detail_view(
-					id,
-					main_scroll_to,
-					tooltip_signals,
-					set_list,
-					list,
-					config.clone(),
-				))
+			move || (active_tab.get(), creating.get()),
+			move |(id, is_creating)| {
+				if is_creating {
+					Box::new(new_entry(NewEntry {
+						title: search_text.get_untracked(),
+						group: active_group.get_untracked(),
+						all_tags,
+						creating,
+						search_text,
+						set_list,
+						set_active_tab,
+						tooltip_signals,
+						config: config.clone(),
+					}))
+				} else if !config.db.read().has_entry(&id) {
+					Box::new(
+						label(|| {
+							"The vault is empty, press enter in the search to add an entry"
+						})
+						.style(|s| s.padding(8.0).color(C_TEXT_MAIN_INACTIVE)),
+					)
+				} else {
+					Box::new(detail_view(
+						id,
+						main_scroll_to,
+						tooltip_signals,
+						set_list,
+						list,
+						all_tags,
+						refresh_list,
+						config.clone(),
+					))
+				}
 			},
 		)
 		.style(|s| {
@@ -346,14 +1034,45 @@ pub fn app_view(config: Config) -> impl View {
 			.class(scroll::Handle, styles::scrollbar_styles)
 	});
 
-	let content =
-		h_stack((sidebar, shadow_box_top, shadow_box_right, dragger, main_window))
-			.style(|s| {
-				s.position(Position::Absolute)
-					.inset_top(SEARCHBAR_HEIGHT)
-					.inset_bottom(0.0)
-					.width_full()
-			});
+	let expired_banner = label(move || {
+		format!(
+			"Past their expiry date: {}. Click to dismiss.",
+			expired_notice.get().join(", ")
+		)
+	})
+	.on_click_stop(move |_| {
+		expired_notice.set(Vec::new());
+	})
+	.style(move |s| {
+		s.position(Position::Absolute)
+			.z_index(4)
+			.inset_top(0)
+			.inset_left(sidebar_width.get() + 10.0)
+			.inset_right(0)
+			.padding(5)
+			.padding_left(10)
+			.font_size(11.0)
+			.color(C_BG_MAIN)
+			.background(C_ERROR)
+			.cursor(CursorStyle::Pointer)
+			.display(Display::None)
+			.apply_if(!expired_notice.get().is_empty(), |s| s.display(Display::Flex))
+	});
+
+	let content = h_stack((
+		sidebar,
+		shadow_box_top,
+		shadow_box_right,
+		dragger,
+		main_window,
+		expired_banner,
+	))
+	.style(|s| {
+		s.position(Position::Absolute)
+			.inset_top(SEARCHBAR_HEIGHT)
+			.inset_bottom(0.0)
+			.width_full()
+	});
 
 	v_stack((tooltip_view(tooltip_signals), search_bar, content))
 		.style(|s| s.width_full().height_full())
@@ -368,6 +1087,79 @@ pub fn app_view(config: Config) -> impl View {
 			}
 			EventPropagation::Continue
 		})
+		.on_event(EventListener::KeyDown, move |event| {
+			let (key, modifiers) = match event {
+				Event::KeyDown(k) => (k.key.physical_key, k.modifiers),
+				_ => (PhysicalKey::Code(KeyCode::F35), ModifiersState::default()),
+			};
+
+			// keys typed into an input belong to it, only the search can copy the
+			// entry picked from the sidebar with the arrow keys
+			let focused = focused_input();
+			let is_typing = focused.is_some();
+			let is_picking =
+				focused == Some(search_text_input_view_id) && selected.get().is_some();
+
+			if key == PhysicalKey::Code(KeyCode::Slash)
+				&& modifiers.is_empty()
+				&& !is_typing
+			{
+				search_text_input_view_id.request_focus();
+				return EventPropagation::Stop;
+			}
+
+			if key == PhysicalKey::Code(KeyCode::KeyC)
+				&& (modifiers == ModifiersState::CONTROL
+					|| modifiers == ModifiersState::CONTROL | ModifiersState::SHIFT)
+				&& (!is_typing || is_picking)
+			{
+				let id = match selected
+					.get()
+					.and_then(|idx| visible_entries.get().get(idx).copied())
+				{
+					Some(id) => id,
+					None => active_tab.get(),
+				};
+				let field = if modifiers.contains(ModifiersState::SHIFT) {
+					DbFields::Username
+				} else {
+					DbFields::Password
+				};
+				let _ = Clipboard::set_contents(
+					config_keys.db.read().get_last_by_field(&id, &field),
+				);
+				config_keys.db.write().mark_used(&id);
+				return EventPropagation::Stop;
+			}
+
+			if key == PhysicalKey::Code(KeyCode::KeyZ)
+				&& (modifiers == ModifiersState::CONTROL
+					|| modifiers == ModifiersState::CONTROL | ModifiersState::SHIFT)
+				&& !is_typing
+			{
+				let changed = if modifiers.contains(ModifiersState::SHIFT) {
+					config_keys.db.write().redo()
+				} else {
+					config_keys.db.write().undo()
+				};
+
+				if let Some(id) = changed {
+					creating.set(false);
+					refresh_list.set(());
+					all_tags.set(config_keys.db.read().get_all_tags());
+					// setting the tab again rebuilds the details with the reverted values
+					if config_keys.db.read().has_entry(&id) {
+						set_active_tab.set(id);
+					} else if let Some(first) = config_keys.db.read().get_list().front() {
+						set_active_tab.set(first.0);
+					} else {
+						creating.set(true);
+					}
+				}
+				return EventPropagation::Stop;
+			}
+			EventPropagation::Continue
+		})
 		.on_resize(move |event| {
 			tooltip_signals.window_size.set((event.x1, event.y1));
 		})
@@ -0,0 +1,174 @@
+use floem::{
+	reactive::{create_rw_signal, RwSignal},
+	style::{AlignItems, Display},
+	view::View,
+	views::{h_stack, label, v_stack, Decorators},
+};
+
+use vault::{
+	config::Config,
+	db::{get_timestamp, Retention},
+};
+
+use crate::ui::{
+	colors::*,
+	primitives::{
+		button::text_button, input_field::input_field, tooltip::TooltipSignals,
+	},
+};
+
+// an empty input means there is no limit
+pub fn parse_retention(
+	versions: &str,
+	days: &str,
+) -> Result<Retention, String> {
+	let max_versions = match versions.trim() {
+		"" => None,
+		value => match value.parse::<usize>() {
+			Ok(0) | Err(_) => {
+				return Err(String::from("Keep at least one version."));
+			}
+			Ok(value) => Some(value),
+		},
+	};
+	let max_age_days = match days.trim() {
+		"" => None,
+		value => match value.parse::<u64>() {
+			Ok(value) => Some(value),
+			Err(_) => return Err(String::from("The days need to be a number.")),
+		},
+	};
+
+	Ok(Retention {
+		max_versions,
+		max_age_days,
+	})
+}
+
+pub fn retention_inputs(
+	versions: RwSignal<String>,
+	days: RwSignal<String>,
+) -> impl View {
+	h_stack((
+		label(|| "Keep the last"),
+		input_field(versions).placeholder("all").style(|s| s.width(45)),
+		label(|| "versions of the last"),
+		input_field(days).placeholder("all").style(|s| s.width(45)),
+		label(|| "days"),
+	))
+	.style(|s| s.items_center().gap(4.0, 0.0))
+}
+
+pub fn to_inputs(retention: Option<Retention>) -> (String, String) {
+	let retention = retention.unwrap_or_default();
+	(
+		retention.max_versions.map(|value| value.to_string()).unwrap_or_default(),
+		retention.max_age_days.map(|value| value.to_string()).unwrap_or_default(),
+	)
+}
+
+pub fn database_view(
+	tooltip_signals: TooltipSignals,
+	config: Config,
+) -> impl View {
+	let retention = create_rw_signal(config.db.read().get_retention());
+	let (versions, days) = to_inputs(Some(retention.get()));
+	let versions = create_rw_signal(versions);
+	let days = create_rw_signal(days);
+	let prunable =
+		create_rw_signal(config.db.read().count_prunable(get_timestamp()));
+	let message = create_rw_signal(String::from(""));
+	let error = create_rw_signal(String::from(""));
+	let confirm_prune = create_rw_signal(false);
+
+	let config_confirm = config.clone();
+	let config_prune = config.clone();
+
+	v_stack((
+		label(|| "History retention").style(|s| s.font_size(16.0)),
+		label(move || retention.get().to_string())
+			.style(|s| s.color(C_TEXT_MAIN_INACTIVE).margin_bottom(5)),
+		retention_inputs(versions, days),
+		label(|| "The current value of a field is always kept.")
+			.style(|s| s.color(C_TEXT_SIDE_INACTIVE).font_size(10.0)),
+		label(move || error.get()).style(move |s| {
+			s.color(C_ERROR)
+				.display(Display::None)
+				.apply_if(!error.get().is_empty(), |s| s.display(Display::Flex))
+		}),
+		text_button(
+			|| String::from("Save retention"),
+			String::from("Applied to the history every time the vault is saved"),
+			tooltip_signals,
+			move |_| match parse_retention(&versions.get(), &days.get()) {
+				Ok(new_retention) => {
+					config.db.write().set_retention(new_retention);
+					retention.set(new_retention);
+					prunable.set(config.db.read().count_prunable(get_timestamp()));
+					confirm_prune.set(false);
+					error.set(String::from(""));
+					message.set(String::from(""));
+				}
+				Err(err) => error.set(err),
+			},
+		),
+		h_stack((
+			label(move || {
+				if message.get().is_empty() {
+					format!("{} old versions would be removed", prunable.get())
+				} else {
+					message.get()
+				}
+			}),
+			text_button(
+				|| String::from("Prune history"),
+				String::from("Remove the old versions now"),
+				tooltip_signals,
+				move |_| {
+					tooltip_signals.hide();
+					// show what would be removed right now before asking
+					prunable
+						.set(config_confirm.db.read().count_prunable(get_timestamp()));
+					message.set(String::from(""));
+					confirm_prune.set(true);
+				},
+			)
+			.style(move |s| {
+				s.apply_if(confirm_prune.get(), |s| s.display(Display::None))
+			}),
+			h_stack((
+				label(|| "Remove them?").style(|s| s.color(C_ERROR)),
+				text_button(
+					|| String::from("Yes"),
+					String::from("This can't be undone"),
+					tooltip_signals,
+					move |_| {
+						tooltip_signals.hide();
+						let removed =
+							config_prune.db.write().prune_history(get_timestamp());
+						prunable.set(0);
+						confirm_prune.set(false);
+						message.set(format!("Removed {} old versions", removed));
+					},
+				),
+				text_button(
+					|| String::from("No"),
+					String::from("Keep the old versions"),
+					tooltip_signals,
+					move |_| {
+						tooltip_signals.hide();
+						confirm_prune.set(false);
+					},
+				),
+			))
+			.style(move |s| {
+				s.items_center()
+					.gap(4.0, 0.0)
+					.display(Display::None)
+					.apply_if(confirm_prune.get(), |s| s.display(Display::Flex))
+			}),
+		))
+		.style(|s| s.align_items(AlignItems::Center).gap(6.0, 0.0).margin_top(10)),
+	))
+	.style(|s| s.padding(8.0).gap(0.0, 5.0))
+}
@@ -0,0 +1,338 @@
+use chrono::{DateTime, Local, Utc};
+use floem::{
+	action::{open_file, save_as},
+	file::FileDialogOptions,
+	reactive::{create_rw_signal, RwSignal},
+	style::{AlignContent, AlignItems, Display},
+	view::View,
+	views::{container, h_stack, label, list, v_stack, Decorators},
+};
+
+use vault::{
+	attachment::{format_size, open_with_default, read_file, write_file},
+	config::Config,
+};
+
+use crate::ui::{
+	colors::*,
+	details::detail_view::{
+		BUTTON_SLOTS_WIDTH, INPUT_LINE_WIDTH, LABEL_WIDTH, LINE_WIDTH,
+	},
+	primitives::{
+		button::{icon_button, text_button, IconButton},
+		tooltip::TooltipSignals,
+	},
+};
+
+struct AttachmentAction {
+	pub id: usize,
+	pub attachment_id: usize,
+	pub n: usize,
+	pub name: String,
+	pub error: RwSignal<String>,
+	pub config: Config,
+}
+
+fn save_attachment(params: AttachmentAction) {
+	let AttachmentAction {
+		id,
+		attachment_id,
+		n,
+		name,
+		error,
+		config,
+	} = params;
+
+	let data = match config.db.read().get_attachment_data(&id, &attachment_id, n)
+	{
+		Ok(data) => data,
+		Err(err) => {
+			error.set(err.to_string());
+			return;
+		}
+	};
+
+	save_as(
+		FileDialogOptions::new().title("Save attachment").default_name(name),
+		move |file| {
+			if let Some(path) = file.and_then(|file| file.path.first().cloned()) {
+				match write_file(&path, &data) {
+					Ok(()) => error.set(String::from("")),
+					Err(err) => error.set(err.to_string()),
+				}
+			}
+		},
+	);
+}
+
+fn open_attachment(params: AttachmentAction) {
+	let AttachmentAction {
+		id,
+		attachment_id,
+		n,
+		name,
+		error,
+		config,
+	} = params;
+
+	let opened = config
+		.db
+		.read()
+		.get_attachment_data(&id, &attachment_id, n)
+		.and_then(|data| open_with_default(&name, &data));
+	match opened {
+		Ok(_) => {
+			config.db.write().mark_used(&id);
+			error.set(String::from(""));
+		}
+		Err(err) => error.set(err.to_string()),
+	}
+}
+
+fn format_date(date: Option<u64>) -> String {
+	match date.and_then(|date| DateTime::<Utc>::from_timestamp(date as i64, 0)) {
+		Some(datetime) => datetime.with_timezone(&Local).format("%v").to_string(),
+		None => String::from("unknown date"),
+	}
+}
+
+fn attachment_line(
+	id: usize,
+	attachment_id: usize,
+	name: String,
+	error: RwSignal<String>,
+	tooltip_signals: TooltipSignals,
+	config: Config,
+) -> impl View {
+	let versions = create_rw_signal(
+		config.db.read().get_attachment_history(&id, &attachment_id),
+	);
+	let show_history = create_rw_signal(false);
+
+	let history_icon = include_str!("../icons/history.svg");
+	let hide_history_icon = include_str!("../icons/hide_history.svg");
+
+	let name_label = name.clone();
+	let name_open = name.clone();
+	let name_save = name.clone();
+	let config_open = config.clone();
+	let config_save = config.clone();
+	let config_replace = config.clone();
+
+	v_stack((
+		h_stack((
+			container(label(|| "Attachment"))
+				.style(|s| s.width(LABEL_WIDTH).justify_content(AlignContent::End)),
+			label(move || {
+				let history = versions.get();
+				let size = history.first().map(|version| version.1).unwrap_or(0);
+				format!("{} ({}, v{})", name_label, format_size(size), history.len())
+			})
+			.style(|s| s.text_ellipsis().width(INPUT_LINE_WIDTH)),
+			h_stack((
+				text_button(
+					|| String::from("Open"),
+					String::from("Open with the default application"),
+					tooltip_signals,
+					move |_| {
+						open_attachment(AttachmentAction {
+							id,
+							attachment_id,
+							n: 0,
+							name: name_open.clone(),
+							error,
+							config: config_open.clone(),
+						});
+					},
+				),
+				text_button(
+					|| String::from("Save"),
+					String::from("Save a decrypted copy to disk"),
+					tooltip_signals,
+					move |_| {
+						save_attachment(AttachmentAction {
+							id,
+							attachment_id,
+							n: 0,
+							name: name_save.clone(),
+							error,
+							config: config_save.clone(),
+						});
+					},
+				),
+				text_button(
+					|| String::from("Replace"),
+					String::from("Store a new version of this attachment"),
+					tooltip_signals,
+					move |_| {
+						let config = config_replace.clone();
+						open_file(
+							FileDialogOptions::new().title("Replace attachment"),
+							move |file| {
+								let Some(path) =
+									file.and_then(|file| file.path.first().cloned())
+								else {
+									return;
+								};
+								let updated = read_file(&path).and_then(|(_, data)| {
+									config.db.write().update_attachment(
+										&id,
+										&attachment_id,
+										&data,
+									)
+								});
+								match updated {
+									Ok(()) => {
+										versions.set(
+											config
+												.db
+												.read()
+												.get_attachment_history(&id, &attachment_id),
+										);
+										error.set(String::from(""));
+									}
+									Err(err) => error.set(err.to_string()),
+								}
+							},
+						);
+					},
+				),
+				icon_button(
+					IconButton {
+						icon: String::from(history_icon),
+						icon2: Some(String::from(hide_history_icon)),
+						bubble: None::<RwSignal<Vec<u8>>>,
+						tooltip: String::from("See versions"),
+						tooltip2: Some(String::from("Hide versions")),
+						switch: Some(show_history),
+						tooltip_signals,
+					},
+					|_| {},
+				),
+			))
+			.style(|s| s.align_items(AlignItems::Center).width(BUTTON_SLOTS_WIDTH)),
+		))
+		.style(|s| s.align_items(AlignItems::Center).gap(4.0, 0.0)),
+		list(
+			move || versions.get().into_iter().enumerate(),
+			move |(n, (date, _))| (*n, *date),
+			move |(n, (date, size))| {
+				let name_open = name.clone();
+				let name_save = name.clone();
+				let config_open = config.clone();
+				let config_save = config.clone();
+
+				h_stack((
+					label(move || {
+						format!("{}  {}", format_date(date), format_size(size))
+					})
+					.style(|s| {
+						s.color(C_TEXT_SIDE_INACTIVE).font_size(9.0).width(INPUT_LINE_WIDTH)
+					}),
+					text_button(
+						|| String::from("Open"),
+						String::from("Open this version"),
+						tooltip_signals,
+						move |_| {
+							open_attachment(AttachmentAction {
+								id,
+								attachment_id,
+								n,
+								name: name_open.clone(),
+								error,
+								config: config_open.clone(),
+							});
+						},
+					),
+					text_button(
+						|| String::from("Save"),
+						String::from("Save this version to disk"),
+						tooltip_signals,
+						move |_| {
+							save_attachment(AttachmentAction {
+								id,
+								attachment_id,
+								n,
+								name: name_save.clone(),
+								error,
+								config: config_save.clone(),
+							});
+						},
+					),
+				))
+				.style(|s| {
+					s.align_items(AlignItems::Center)
+						.gap(4.0, 0.0)
+						.margin_left(LABEL_WIDTH + 4.0)
+				})
+			},
+		)
+		.style(move |s| {
+			s.flex_col()
+				.display(Display::None)
+				.apply_if(show_history.get(), |s| s.display(Display::Flex))
+		}),
+	))
+	.style(|s| s.width(LINE_WIDTH).gap(0.0, 3.0))
+}
+
+pub fn attachments(
+	id: usize,
+	tooltip_signals: TooltipSignals,
+	config: Config,
+) -> impl View {
+	let attachment_list = create_rw_signal(config.db.read().get_attachments(&id));
+	let error = create_rw_signal(String::from(""));
+
+	let config_add = config.clone();
+
+	v_stack((
+		list(
+			move || attachment_list.get(),
+			|item| item.0,
+			move |(attachment_id, name)| {
+				attachment_line(
+					id,
+					attachment_id,
+					name,
+					error,
+					tooltip_signals,
+					config.clone(),
+				)
+			},
+		)
+		.style(|s| s.flex_col().gap(0.0, 5.0)),
+		label(move || error.get()).style(move |s| {
+			s.color(C_ERROR)
+				.margin_left(LABEL_WIDTH + 4.0)
+				.display(Display::None)
+				.apply_if(!error.get().is_empty(), |s| s.display(Display::Flex))
+		}),
+		container(text_button(
+			|| String::from("Attach file"),
+			String::from("Store an encrypted copy of a file in this entry"),
+			tooltip_signals,
+			move |_| {
+				let config = config_add.clone();
+				open_file(FileDialogOptions::new().title("Attach file"), move |file| {
+					let Some(path) = file.and_then(|file| file.path.first().cloned())
+					else {
+						return;
+					};
+					let added = read_file(&path).and_then(|(name, data)| {
+						config.db.write().add_attachment(&id, name, &data)
+					});
+					match added {
+						Ok(_) => {
+							attachment_list.set(config.db.read().get_attachments(&id));
+							error.set(String::from(""));
+						}
+						Err(err) => error.set(err.to_string()),
+					}
+				});
+			},
+		))
+		.style(|s| s.margin_left(LABEL_WIDTH + 4.0)),
+	))
+	.style(|s| s.gap(0.0, 5.0).margin_bottom(10))
+}
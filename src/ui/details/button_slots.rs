@@ -7,19 +7,17 @@ use floem::{
 	Clipboard,
 };
 
-use crate::{
-	config::Config,
-	db::DbFields,
-	ui::{
-		details::detail_view::{save_edit, SaveEdit, SECRET_PLACEHOLDER},
-		history_view::history_view,
-		primitives::{
-			button::{icon_button, IconButton},
-			tooltip::TooltipSignals,
-		},
-		window_management::{
-			closing_window, make_field_path, opening_window, WindowSpec,
-		},
+use vault::{config::Config, db::DbFields};
+
+use crate::ui::{
+	details::detail_view::{save_edit, SaveEdit, SECRET_PLACEHOLDER},
+	history_view::history_view,
+	primitives::{
+		button::{icon_button, IconButton},
+		tooltip::TooltipSignals,
+	},
+	window_management::{
+		closing_window, make_field_path, opening_window, WindowSpec,
 	},
 };
 
@@ -30,7 +28,7 @@ pub struct EditButtonSlot {
 	pub is_hidden: bool,
 	pub is_secret: bool,
 	pub input_id: Id,
-	pub dates: RwSignal<Vec<(usize, u64)>>,
+	pub dates: RwSignal<Vec<(usize, Option<u64>)>>,
 	pub field_value: RwSignal<String>,
 	pub reset_text: RwSignal<String>,
 	pub set_list: WriteSignal<im::Vector<(usize, &'static str, usize)>>,
@@ -165,7 +163,7 @@ pub fn clipboard_button_slot(
 pub struct HistoryButtonSlot {
 	pub id: usize,
 	pub field: DbFields,
-	pub dates: RwSignal<Vec<(usize, u64)>>,
+	pub dates: RwSignal<Vec<(usize, Option<u64>)>>,
 	pub is_secret: bool,
 	pub field_title: String,
 	pub tooltip_signals: TooltipSignals,
@@ -187,7 +185,7 @@ pub fn history_button_slot(param: HistoryButtonSlot) -> impl View {
 
 	let hide_history_btn_visible = create_rw_signal(false);
 
-	if is_secret {
+	if field != DbFields::Id {
 		let config_history = config.clone();
 
 		container(icon_button(
@@ -203,15 +201,17 @@ pub fn history_button_slot(param: HistoryButtonSlot) -> impl View {
 			move |_| {
 				if hide_history_btn_visible.get() {
 					let config_history_inner = config_history.clone();
+					let field_title_inner = field_title.clone();
 					let window_title = format!("{} Field History", field_title);
-					let dates_window = dates.get();
 
 					opening_window(
 						move || {
 							history_view(
 								id,
 								field,
-								dates_window.clone(),
+								field_title_inner.clone(),
+								dates,
+								is_secret,
 								config_history_inner.clone(),
 							)
 						},
@@ -219,7 +219,7 @@ pub fn history_button_slot(param: HistoryButtonSlot) -> impl View {
 							id: make_field_path(id, &field),
 							title: window_title,
 						},
-						Size::new(350.0, 300.0),
+						Size::new(480.0, 320.0),
 						move || {
 							hide_history_btn_visible.set(false);
 						},
@@ -3,7 +3,7 @@ use floem::{
 	reactive::{
 		create_rw_signal, create_signal, ReadSignal, RwSignal, WriteSignal,
 	},
-	style::{AlignContent, AlignItems},
+	style::{AlignContent, AlignItems, Display},
 	view::View,
 	views::{
 		h_stack, label, svg, v_stack, virtual_stack, Decorators, VirtualDirection,
@@ -11,16 +11,25 @@ use floem::{
 	},
 };
 
-use crate::{
+use vault::{
 	config::Config,
-	db::DbFields,
-	ui::{
-		details::{
-			hidden_fields::{hidden_fields, HiddeFields},
-			list_item::{list_item, ListItem},
-			new_field::new_field,
-		},
-		primitives::tooltip::TooltipSignals,
+	db::{DbFields, DynamicFieldKind, EntryKind},
+};
+
+use crate::ui::{
+	details::{
+		attachments::attachments,
+		entry_info::entry_info,
+		hidden_fields::{hidden_fields, HiddeFields},
+		list_item::{list_item, ListItem},
+		move_buttons::{move_buttons, MoveButtons},
+		new_field::new_field,
+		note_item::{note_item, NoteItem},
+		tag_list::tag_list,
+	},
+	primitives::{
+		button::{icon_button, IconButton},
+		tooltip::TooltipSignals,
 	},
 };
 
@@ -34,7 +43,7 @@ pub struct SaveEdit {
 	pub id: usize,
 	pub field: DbFields,
 	pub value: RwSignal<String>,
-	pub dates: RwSignal<Vec<(usize, u64)>>,
+	pub dates: RwSignal<Vec<(usize, Option<u64>)>>,
 	pub is_secret: bool,
 	pub input_id: Id,
 	pub set_list: WriteSignal<im::Vector<(usize, &'static str, usize)>>,
@@ -54,6 +63,18 @@ pub fn save_edit(params: SaveEdit) {
 	} = params;
 
 	let last_val = config.db.read().get_last_by_field(&id, &field);
+
+	// values that don't fit the kind of the field are never stored
+	let is_valid = config
+		.db
+		.read()
+		.get_dyn_field_kind(&id, &field)
+		.map(|kind| kind.is_valid(&value.get()))
+		.unwrap_or(true);
+	if !is_valid {
+		value.set(last_val.clone());
+	}
+
 	if last_val != value.get() {
 		config.db.write().edit_field(id, &field, value.get());
 		if field == DbFields::Title {
@@ -78,9 +99,16 @@ pub fn detail_view(
 	tooltip_signals: TooltipSignals,
 	set_list: WriteSignal<im::Vector<(usize, &'static str, usize)>>,
 	list: ReadSignal<im::Vector<(usize, &'static str, usize)>>,
+	all_tags: RwSignal<Vec<(String, usize)>>,
+	refresh_list: RwSignal<()>,
 	config: Config,
 ) -> impl View {
 	let password_icon = include_str!("../icons/password.svg");
+	let star_icon = include_str!("../icons/star.svg");
+	let star_filled_icon = include_str!("../icons/star_filled.svg");
+
+	let is_favorite = create_rw_signal(config.db.read().is_favorite(&id));
+	let config_favorite = config.clone();
 
 	let field_list: im::Vector<DbFields> =
 		config.db.read().get_dyn_fields(&id).into();
@@ -92,7 +120,18 @@ pub fn detail_view(
 	let (hidden_field_list, set_hidden_field_list) =
 		create_signal(hidden_field_list);
 
+	let is_login = config.db.read().get_entry_kind(&id) == EntryKind::Login;
+
+	// notes grow with their content so they can't live in the fixed height list
+	let config_kind = config.clone();
+	let is_note = move |field: &DbFields| {
+		config_kind.db.read().get_dyn_field_kind(&id, field)
+			== Some(DynamicFieldKind::Note)
+	};
+	let is_note_list = is_note.clone();
+
 	let config_fields = config.clone();
+	let config_notes = config.clone();
 
 	v_stack((
 		h_stack((
@@ -107,6 +146,21 @@ pub fn detail_view(
 					.1
 			})
 			.style(|s| s.font_size(24.0)),
+			icon_button(
+				IconButton {
+					icon: String::from(star_icon),
+					icon2: Some(String::from(star_filled_icon)),
+					bubble: None::<RwSignal<Vec<u8>>>,
+					tooltip: String::from("Pin as favorite"),
+					tooltip2: Some(String::from("Unpin from favorites")),
+					switch: Some(is_favorite),
+					tooltip_signals,
+				},
+				move |_| {
+					config_favorite.db.write().toggle_favorite(&id);
+					refresh_list.set(());
+				},
+			),
 		))
 		.style(|s| {
 			s.align_items(AlignItems::Center)
@@ -138,7 +192,8 @@ pub fn detail_view(
 				tooltip_signals,
 				set_list,
 				config: config.clone(),
-			}),
+			})
+			.style(move |s| s.apply_if(!is_login, |s| s.display(Display::None))),
 			list_item(ListItem {
 				id,
 				field: DbFields::Username,
@@ -150,7 +205,8 @@ pub fn detail_view(
 				tooltip_signals,
 				set_list,
 				config: config.clone(),
-			}),
+			})
+			.style(move |s| s.apply_if(!is_login, |s| s.display(Display::None))),
 			list_item(ListItem {
 				id,
 				field: DbFields::Password,
@@ -162,29 +218,85 @@ pub fn detail_view(
 				tooltip_signals,
 				set_list,
 				config: config.clone(),
-			}),
+			})
+			.style(move |s| s.apply_if(!is_login, |s| s.display(Display::None))),
+			tag_list(id, all_tags, tooltip_signals, config.clone()),
+			entry_info(id, refresh_list, tooltip_signals, config.clone()),
 			virtual_stack(
 				VirtualDirection::Vertical,
 				VirtualItemSize::Fixed(Box::new(|| 35.0)),
-				move || dyn_field_list.get(),
+				move || {
+					dyn_field_list
+						.get()
+						.into_iter()
+						.filter(|field| !is_note(field))
+						.collect::<im::Vector<DbFields>>()
+				},
 				move |item| *item,
 				move |field| {
-					list_item(ListItem {
-						id,
-						field,
-						set_hidden_field_list,
-						set_dyn_field_list,
-						hidden_field_len,
-						is_secret: true,
-						is_hidden: false,
-						tooltip_signals,
-						set_list,
-						config: config_fields.clone(),
-					})
-					.style(|s| s.padding_bottom(5))
+					let is_secret = config_fields
+						.db
+						.read()
+						.get_dyn_field_kind(&id, &field)
+						.map(|kind| kind.is_secret())
+						.unwrap_or(true);
+					h_stack((
+						list_item(ListItem {
+							id,
+							field,
+							set_hidden_field_list,
+							set_dyn_field_list,
+							hidden_field_len,
+							is_secret,
+							is_hidden: false,
+							tooltip_signals,
+							set_list,
+							config: config_fields.clone(),
+						}),
+						move_buttons(MoveButtons {
+							id,
+							field,
+							set_dyn_field_list,
+							tooltip_signals,
+							config: config_fields.clone(),
+						}),
+					))
+					.style(|s| s.items_center().padding_bottom(5))
 				},
 			)
 			.style(|s| s.margin_bottom(10)),
+			floem::views::list(
+				move || {
+					dyn_field_list
+						.get()
+						.into_iter()
+						.filter(|field| is_note_list(field))
+						.collect::<Vec<DbFields>>()
+				},
+				move |item| *item,
+				move |field| {
+					h_stack((
+						note_item(NoteItem {
+							id,
+							field,
+							set_hidden_field_list,
+							set_dyn_field_list,
+							hidden_field_len,
+							tooltip_signals,
+							config: config_notes.clone(),
+						}),
+						move_buttons(MoveButtons {
+							id,
+							field,
+							set_dyn_field_list,
+							tooltip_signals,
+							config: config_notes.clone(),
+						}),
+					))
+					.style(|s| s.items_start())
+				},
+			)
+			.style(|s| s.flex_col().margin_bottom(10)),
 			hidden_fields(HiddeFields {
 				id,
 				hidden_field_list,
@@ -202,8 +314,9 @@ pub fn detail_view(
 				set_dyn_field_list,
 				tooltip_signals,
 				main_scroll_to,
-				config,
+				config.clone(),
 			),
+			attachments(id, tooltip_signals, config),
 		))
 		.style(|s| s.gap(0, 5)),
 	))
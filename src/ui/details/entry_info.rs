@@ -0,0 +1,269 @@
+use floem::{
+	event::{Event, EventListener},
+	keyboard::{KeyCode, PhysicalKey},
+	reactive::{create_rw_signal, RwSignal},
+	style::{AlignContent, AlignItems, Display},
+	view::View,
+	views::{container, h_stack, label, v_stack, Decorators},
+	EventPropagation,
+};
+
+use vault::{
+	config::Config,
+	db::{format_date, get_timestamp, parse_date, EntryKind, ExpiryStatus},
+};
+
+use crate::ui::{
+	colors::*,
+	details::detail_view::{
+		BUTTON_SLOTS_WIDTH, INPUT_LINE_WIDTH, LABEL_WIDTH, LINE_WIDTH,
+	},
+	password_age_view::parse_max_age,
+	primitives::{
+		button::text_button, input_field::input_field, tooltip::TooltipSignals,
+	},
+};
+
+struct SaveExpiry {
+	pub id: usize,
+	pub expiry_value: RwSignal<String>,
+	pub expires: RwSignal<Option<u64>>,
+	pub error: RwSignal<String>,
+	pub refresh_list: RwSignal<()>,
+	pub config: Config,
+}
+
+// an empty input removes the expiry date
+fn save_expiry(params: SaveExpiry) {
+	let SaveExpiry {
+		id,
+		expiry_value,
+		expires,
+		error,
+		refresh_list,
+		config,
+	} = params;
+
+	let new_expiry = if expiry_value.get().trim().is_empty() {
+		None
+	} else {
+		match parse_date(&expiry_value.get()) {
+			Some(date) => Some(date),
+			None => {
+				error.set(String::from("Use a date like 2024-12-31."));
+				return;
+			}
+		}
+	};
+
+	config.db.write().set_expiry(&id, new_expiry);
+	expires.set(new_expiry);
+	error.set(String::from(""));
+	refresh_list.set(());
+}
+
+struct SaveMaxAge {
+	pub id: usize,
+	pub max_age_value: RwSignal<String>,
+	pub error: RwSignal<String>,
+	pub refresh_list: RwSignal<()>,
+	pub config: Config,
+}
+
+// an empty input uses the vault wide maximum age again
+fn save_max_age(params: SaveMaxAge) {
+	let SaveMaxAge {
+		id,
+		max_age_value,
+		error,
+		refresh_list,
+		config,
+	} = params;
+
+	match parse_max_age(&max_age_value.get()) {
+		Ok(days) => {
+			config.db.write().set_entry_password_max_age(&id, days);
+			error.set(String::from(""));
+			refresh_list.set(());
+		}
+		Err(err) => error.set(err),
+	}
+}
+
+fn describe_date(date: Option<u64>, fallback: &str) -> String {
+	date.map(format_date).unwrap_or_else(|| String::from(fallback))
+}
+
+pub fn entry_info(
+	id: usize,
+	refresh_list: RwSignal<()>,
+	tooltip_signals: TooltipSignals,
+	config: Config,
+) -> impl View {
+	let created = describe_date(config.db.read().get_created(&id), "unknown");
+	let modified = describe_date(config.db.read().get_modified(&id), "unknown");
+	let last_used = describe_date(config.db.read().get_last_used(&id), "never");
+
+	let is_login = config.db.read().get_entry_kind(&id) == EntryKind::Login;
+
+	let expires = create_rw_signal(config.db.read().get_expiry(&id));
+	let expiry_value = create_rw_signal(
+		expires.get_untracked().map(format_date).unwrap_or_default(),
+	);
+	let error = create_rw_signal(String::from(""));
+
+	let max_age_value = create_rw_signal(
+		config
+			.db
+			.read()
+			.get_entry_password_max_age(&id)
+			.map(|days| days.to_string())
+			.unwrap_or_default(),
+	);
+	let max_age_placeholder = match config.db.read().get_password_max_age() {
+		Some(days) => format!("{} (vault)", days),
+		None => String::from("never (vault)"),
+	};
+	let max_age_error = create_rw_signal(String::from(""));
+
+	let status_text = move || {
+		if !error.get().is_empty() {
+			return error.get();
+		}
+		expires
+			.get()
+			.and_then(|date| ExpiryStatus::of(date, get_timestamp()))
+			.map(|status| status.to_string())
+			.unwrap_or_default()
+	};
+
+	let config_enter = config.clone();
+	let config_max_age = config.clone();
+	let config_max_age_enter = config.clone();
+
+	v_stack((
+		h_stack((
+			container(label(|| "Dates"))
+				.style(|s| s.width(LABEL_WIDTH).justify_content(AlignContent::End)),
+			label(move || {
+				format!(
+					"Created {} · Modified {} · Used {}",
+					created, modified, last_used
+				)
+			})
+			.style(|s| {
+				s.width(INPUT_LINE_WIDTH).font_size(11.0).color(C_TEXT_MAIN_INACTIVE)
+			}),
+		))
+		.style(|s| s.align_items(AlignItems::Center).gap(4.0, 0.0)),
+		h_stack((
+			container(label(|| "Expires")).style(|s| {
+				s.width(LABEL_WIDTH).justify_content(AlignContent::End).padding_top(5)
+			}),
+			v_stack((
+				input_field(expiry_value)
+					.placeholder("YYYY-MM-DD")
+					.style(|s| s.width_full())
+					.on_event(EventListener::KeyDown, move |event| {
+						let key = match event {
+							Event::KeyDown(k) => k.key.physical_key,
+							_ => PhysicalKey::Code(KeyCode::F35),
+						};
+
+						if key == PhysicalKey::Code(KeyCode::Enter) {
+							save_expiry(SaveExpiry {
+								id,
+								expiry_value,
+								expires,
+								error,
+								refresh_list,
+								config: config_enter.clone(),
+							});
+						}
+						EventPropagation::Continue
+					}),
+				label(status_text).style(move |s| {
+					s.font_size(10.0)
+						.color(C_ERROR)
+						.display(Display::None)
+						.apply_if(!status_text().is_empty(), |s| s.display(Display::Flex))
+				}),
+			))
+			.style(|s| s.width(INPUT_LINE_WIDTH)),
+			container(text_button(
+				|| String::from("Save"),
+				String::from("Save the expiry date, leave it empty to remove it"),
+				tooltip_signals,
+				move |_| {
+					save_expiry(SaveExpiry {
+						id,
+						expiry_value,
+						expires,
+						error,
+						refresh_list,
+						config: config.clone(),
+					});
+				},
+			))
+			.style(|s| s.align_items(AlignItems::Center).width(BUTTON_SLOTS_WIDTH)),
+		))
+		.style(|s| s.align_items(AlignItems::Start).gap(4.0, 0.0)),
+		h_stack((
+			container(label(|| "Rotate days")).style(|s| {
+				s.width(LABEL_WIDTH).justify_content(AlignContent::End).padding_top(5)
+			}),
+			v_stack((
+				input_field(max_age_value)
+					.placeholder(max_age_placeholder)
+					.style(|s| s.width_full())
+					.on_event(EventListener::KeyDown, move |event| {
+						let key = match event {
+							Event::KeyDown(k) => k.key.physical_key,
+							_ => PhysicalKey::Code(KeyCode::F35),
+						};
+
+						if key == PhysicalKey::Code(KeyCode::Enter) {
+							save_max_age(SaveMaxAge {
+								id,
+								max_age_value,
+								error: max_age_error,
+								refresh_list,
+								config: config_max_age_enter.clone(),
+							});
+						}
+						EventPropagation::Continue
+					}),
+				label(move || max_age_error.get()).style(move |s| {
+					s.font_size(10.0)
+						.color(C_ERROR)
+						.display(Display::None)
+						.apply_if(!max_age_error.get().is_empty(), |s| {
+							s.display(Display::Flex)
+						})
+				}),
+			))
+			.style(|s| s.width(INPUT_LINE_WIDTH)),
+			container(text_button(
+				|| String::from("Save"),
+				String::from("Days until the password should be changed"),
+				tooltip_signals,
+				move |_| {
+					save_max_age(SaveMaxAge {
+						id,
+						max_age_value,
+						error: max_age_error,
+						refresh_list,
+						config: config_max_age.clone(),
+					});
+				},
+			))
+			.style(|s| s.align_items(AlignItems::Center).width(BUTTON_SLOTS_WIDTH)),
+		))
+		.style(move |s| {
+			s.align_items(AlignItems::Start)
+				.gap(4.0, 0.0)
+				.apply_if(!is_login, |s| s.display(Display::None))
+		}),
+	))
+	.style(|s| s.gap(0, 5).width(LINE_WIDTH))
+}
@@ -10,16 +10,94 @@ use floem::{
 	EventPropagation,
 };
 
-use crate::{
-	config::Config,
-	db::DbFields,
-	ui::{
-		colors::*,
-		details::list_item::{list_item, ListItem},
-		primitives::tooltip::TooltipSignals,
-	},
+use vault::{config::Config, db::DbFields};
+
+use crate::ui::{
+	colors::*,
+	details::list_item::{list_item, ListItem},
+	primitives::{button::text_button, tooltip::TooltipSignals},
+	window_management::{closing_window, make_field_path},
 };
 
+struct DeleteForever {
+	id: usize,
+	field: DbFields,
+	confirm_delete: RwSignal<Option<DbFields>>,
+	set_hidden_field_list: WriteSignal<im::Vector<DbFields>>,
+	hidden_field_len: RwSignal<usize>,
+	tooltip_signals: TooltipSignals,
+	config: Config,
+}
+
+// archived fields can be deleted for good after confirming it
+fn delete_forever(param: DeleteForever) -> impl View {
+	let DeleteForever {
+		id,
+		field,
+		confirm_delete,
+		set_hidden_field_list,
+		hidden_field_len,
+		tooltip_signals,
+		config,
+	} = param;
+
+	h_stack((
+		text_button(
+			|| String::from("Delete"),
+			String::from("Delete this field and all of its versions"),
+			tooltip_signals,
+			move |_| {
+				tooltip_signals.hide();
+				confirm_delete.set(Some(field));
+			},
+		)
+		.style(move |s| {
+			s.apply_if(confirm_delete.get() == Some(field), |s| {
+				s.display(Display::None)
+			})
+		}),
+		h_stack((
+			label(|| "Delete all versions?")
+				.style(|s| s.color(C_ERROR).font_size(11.0)),
+			text_button(
+				|| String::from("Yes"),
+				String::from("This can't be undone"),
+				tooltip_signals,
+				move |_| {
+					tooltip_signals.hide();
+					let hidden_field_list: im::Vector<DbFields> =
+						config.db.write().delete_dyn_field(&id, &field).into();
+					hidden_field_len.set(hidden_field_list.len());
+					set_hidden_field_list.set(hidden_field_list);
+					confirm_delete.set(None);
+					// the history of the field is gone as well
+					let path = make_field_path(id, &field);
+					closing_window(format!("{}-diff", path), || {});
+					closing_window(path, || {});
+				},
+			),
+			text_button(
+				|| String::from("No"),
+				String::from("Keep the field"),
+				tooltip_signals,
+				move |_| {
+					tooltip_signals.hide();
+					confirm_delete.set(None);
+				},
+			),
+		))
+		.style(move |s| {
+			s.items_center()
+				.gap(4.0, 0.0)
+				.display(Display::None)
+				.apply_if(confirm_delete.get() == Some(field), |s| {
+					s.display(Display::Flex)
+				})
+		}),
+	))
+	.style(|s| s.items_center().height(30))
+}
+
 pub struct HiddeFields {
 	pub id: usize,
 	pub hidden_field_list: ReadSignal<im::Vector<DbFields>>,
@@ -45,6 +123,7 @@ pub fn hidden_fields(param: HiddeFields) -> impl View {
 		config,
 	} = param;
 	let is_expanded = create_rw_signal(false);
+	let confirm_delete = create_rw_signal(None::<DbFields>);
 
 	let expand_icon = include_str!("../icons/expand.svg");
 	let contract_icon = include_str!("../icons/contract.svg");
@@ -61,19 +140,36 @@ pub fn hidden_fields(param: HiddeFields) -> impl View {
 				move || hidden_field_list.get(),
 				move |item| *item,
 				move |field| {
-					list_item(ListItem {
-						id,
-						field,
-						set_hidden_field_list,
-						set_dyn_field_list,
-						hidden_field_len,
-						is_secret: true,
-						is_hidden: true,
-						tooltip_signals,
-						set_list,
-						config: config.clone(),
-					})
-					.style(|s| s.padding_bottom(5))
+					let is_secret = config
+						.db
+						.read()
+						.get_dyn_field_kind(&id, &field)
+						.map(|kind| kind.is_secret())
+						.unwrap_or(true);
+					h_stack((
+						list_item(ListItem {
+							id,
+							field,
+							set_hidden_field_list,
+							set_dyn_field_list,
+							hidden_field_len,
+							is_secret,
+							is_hidden: true,
+							tooltip_signals,
+							set_list,
+							config: config.clone(),
+						}),
+						delete_forever(DeleteForever {
+							id,
+							field,
+							confirm_delete,
+							set_hidden_field_list,
+							hidden_field_len,
+							tooltip_signals,
+							config: config.clone(),
+						}),
+					))
+					.style(|s| s.items_center().padding_bottom(5))
 				},
 			)
 			.style(|s| s.display(Display::Flex)),
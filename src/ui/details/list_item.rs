@@ -1,33 +1,36 @@
 use floem::{
+	action::exec_after,
 	event::{Event, EventListener},
 	keyboard::{KeyCode, PhysicalKey},
-	reactive::{create_rw_signal, RwSignal, WriteSignal},
+	reactive::{create_effect, create_rw_signal, RwSignal, WriteSignal},
 	style::{AlignItems, CursorStyle, Display, Position},
 	view::View,
 	views::{container, h_stack, label, svg, Decorators},
 	EventPropagation,
 };
+use std::time::Duration;
 use url_escape;
 use webbrowser;
 
-use crate::{
+use vault::{
 	config::Config,
-	db::DbFields,
-	ui::{
-		colors::*,
-		details::{
-			button_slots::{
-				clipboard_button_slot, delete_button_slot, edit_button_slot,
-				history_button_slot, view_button_slot, DeleteButtonSlot,
-				EditButtonSlot, HistoryButtonSlot, ViewButtonSlot,
-			},
-			detail_view::{
-				save_edit, SaveEdit, INPUT_LINE_WIDTH, LINE_WIDTH, SECRET_PLACEHOLDER,
-			},
-			dyn_field_title_form::{dyn_field_title_form, DynFieldTitleForm},
+	db::{get_timestamp, DbFields, DynamicFieldKind},
+};
+
+use crate::ui::{
+	colors::*,
+	details::{
+		button_slots::{
+			clipboard_button_slot, delete_button_slot, edit_button_slot,
+			history_button_slot, view_button_slot, DeleteButtonSlot, EditButtonSlot,
+			HistoryButtonSlot, ViewButtonSlot,
+		},
+		detail_view::{
+			save_edit, SaveEdit, INPUT_LINE_WIDTH, LINE_WIDTH, SECRET_PLACEHOLDER,
 		},
-		primitives::{input_field::input_field, tooltip::TooltipSignals},
+		dyn_field_title_form::{dyn_field_title_form, DynFieldTitleForm},
 	},
+	primitives::{input_field::input_field, tooltip::TooltipSignals},
 };
 
 pub struct ListItem {
@@ -43,6 +46,16 @@ pub struct ListItem {
 	pub config: Config,
 }
 
+// keep a timestamp signal up to date so one-time codes and countdowns refresh
+fn otp_ticker(now: RwSignal<u64>) {
+	exec_after(Duration::from_secs(1), move |_| {
+		// the signal is disposed once the view has been destroyed which ends the loop
+		if now.try_update(|now| *now = get_timestamp()).is_some() {
+			otp_ticker(now);
+		}
+	});
+}
+
 pub fn list_item(param: ListItem) -> impl View {
 	let ListItem {
 		id,
@@ -75,6 +88,41 @@ pub fn list_item(param: ListItem) -> impl View {
 	};
 
 	let is_dyn_field = matches!(field, DbFields::Fields(_));
+	let field_kind = config.db.read().get_dyn_field_kind(&id, &field);
+	let is_valid = move |value: &str| {
+		value.is_empty()
+			|| field_kind.map(|kind| kind.is_valid(value)).unwrap_or(true)
+	};
+	let is_totp = field_kind == Some(DynamicFieldKind::Totp);
+	let is_hotp = field_kind == Some(DynamicFieldKind::Hotp);
+	let hotp_code = create_rw_signal(String::from(""));
+	let is_link = matches!(field, DbFields::Url)
+		|| matches!(
+			field_kind,
+			Some(DynamicFieldKind::Url)
+				| Some(DynamicFieldKind::Email)
+				| Some(DynamicFieldKind::Phone)
+		);
+
+	let now = create_rw_signal(get_timestamp());
+	if is_totp {
+		otp_ticker(now);
+	}
+
+	// versions restored from the history window change the current value
+	let config_restored = config.clone();
+	create_effect(move |prev: Option<()>| {
+		dates.track();
+		if prev.is_none() || edit_button_switch.get_untracked() {
+			return;
+		}
+		if !is_secret || view_button_switch.get_untracked() {
+			field_value.set(config_restored.db.read().get_last_by_field(&id, &field));
+		}
+		if field == DbFields::Title {
+			set_list.set(config_restored.db.read().get_list());
+		}
+	});
 
 	let revert_icon = include_str!("../icons/revert.svg");
 
@@ -84,6 +132,7 @@ pub fn list_item(param: ListItem) -> impl View {
 	let config_viewbtn = config.clone();
 	let config_history = config.clone();
 	let config_deletebtn = config.clone();
+	let config_otp = config.clone();
 
 	let input = input_field(field_value);
 	let input_id = input.id();
@@ -95,6 +144,9 @@ pub fn list_item(param: ListItem) -> impl View {
 			.style(move |s| {
 				s.width(INPUT_LINE_WIDTH)
 					.padding_right(30)
+					.apply_if(!is_valid(&field_value.get()), |s| {
+						s.border_color(C_ERROR).focus(|s| s.border_color(C_ERROR))
+					})
 					.display(Display::None)
 					.apply_if(edit_button_switch.get(), |s| s.display(Display::Flex))
 			})
@@ -192,32 +244,53 @@ pub fn list_item(param: ListItem) -> impl View {
 		),
 		h_stack((
 			input_line,
-			label(move || field_value.get())
-				.style(move |s| {
-					s.width(INPUT_LINE_WIDTH)
-						.padding_top(5)
-						.padding_right(6)
-						.padding_left(6)
-						.padding_bottom(5)
-						.border_bottom(1)
-						.border_color(C_TEXT_TOP)
-						.apply_if(is_hidden, |s| s.border_color(C_TEXT_MAIN_INACTIVE))
-						.display(Display::Flex)
-						.apply_if(edit_button_switch.get(), |s| s.display(Display::None))
-						.hover(|s| {
-							s.apply_if(matches!(field, DbFields::Url), |s| {
-								s.color(C_FOCUS).cursor(CursorStyle::Pointer)
-							})
-						})
-				})
-				.on_click(move |_| {
-					if matches!(field, DbFields::Url) {
-						let _ = webbrowser::open(&url_escape::encode_fragment(
-							&field_value.get(),
-						));
+			label(move || {
+				if is_totp && !view_button_switch.get() {
+					match config_otp.db.read().get_totp_code(&id, &field, now.get()) {
+						Ok((code, remaining)) => format!("{}  ({}s)", code, remaining),
+						Err(error) => error.to_string(),
 					}
-					EventPropagation::Continue
-				}),
+				} else if is_hotp && !view_button_switch.get() {
+					dates.track();
+					match config_otp.db.read().get_hotp_counter(&id, &field) {
+						Ok(counter) if hotp_code.get().is_empty() => {
+							format!("Copy to generate code #{}", counter)
+						}
+						Ok(counter) => format!("{}  (next #{})", hotp_code.get(), counter),
+						Err(error) => error.to_string(),
+					}
+				} else {
+					field_value.get()
+				}
+			})
+			.style(move |s| {
+				s.width(INPUT_LINE_WIDTH)
+					.padding_top(5)
+					.padding_right(6)
+					.padding_left(6)
+					.padding_bottom(5)
+					.border_bottom(1)
+					.border_color(C_TEXT_TOP)
+					.apply_if(is_hidden, |s| s.border_color(C_TEXT_MAIN_INACTIVE))
+					.display(Display::Flex)
+					.apply_if(edit_button_switch.get(), |s| s.display(Display::None))
+					.hover(|s| {
+						s.apply_if(is_link, |s| {
+							s.color(C_FOCUS).cursor(CursorStyle::Pointer)
+						})
+					})
+			})
+			.on_click(move |_| {
+				if matches!(field, DbFields::Url) {
+					let _ =
+						webbrowser::open(&url_escape::encode_fragment(&field_value.get()));
+				} else if let Some(link) =
+					field_kind.and_then(|kind| kind.link(&field_value.get()))
+				{
+					let _ = webbrowser::open(&link);
+				}
+				EventPropagation::Continue
+			}),
 		)),
 		edit_button_slot(EditButtonSlot {
 			id,
@@ -235,7 +308,28 @@ pub fn list_item(param: ListItem) -> impl View {
 			config: config_edit,
 		}),
 		clipboard_button_slot(tooltip_signals, move || {
-			config.db.read().get_last_by_field(&id, &field)
+			let value = if is_hotp {
+				let code =
+					config.db.write().generate_hotp_code(&id, &field).unwrap_or_default();
+				hotp_code.set(code.clone());
+				dates.set(config.db.read().get_history_dates(&id, &field));
+				code
+			} else if is_totp {
+				config
+					.db
+					.read()
+					.get_totp_code(&id, &field, get_timestamp())
+					.map(|(code, _)| code)
+					.unwrap_or_default()
+			} else {
+				let value = config.db.read().get_last_by_field(&id, &field);
+				match field_kind {
+					Some(kind) => kind.copy_value(&value),
+					None => value,
+				}
+			};
+			config.db.write().mark_used(&id);
+			value
 		}),
 		view_button_slot(
 			ViewButtonSlot {
@@ -0,0 +1,92 @@
+use floem::{
+	event::EventListener,
+	reactive::WriteSignal,
+	style::CursorStyle,
+	view::View,
+	views::{label, v_stack, Decorators},
+	EventPropagation,
+};
+
+use vault::{
+	config::Config,
+	db::{DbFields, DynamicFieldKind},
+};
+
+use crate::ui::{colors::*, primitives::tooltip::TooltipSignals};
+
+pub struct MoveButtons {
+	pub id: usize,
+	pub field: DbFields,
+	pub set_dyn_field_list: WriteSignal<im::Vector<DbFields>>,
+	pub tooltip_signals: TooltipSignals,
+	pub config: Config,
+}
+
+// notes are listed on their own so they only swap with other notes
+fn neighbour(
+	id: usize,
+	field: DbFields,
+	up: bool,
+	config: &Config,
+) -> Option<DbFields> {
+	let db = config.db.read();
+	let is_note = |field: &DbFields| {
+		db.get_dyn_field_kind(&id, field) == Some(DynamicFieldKind::Note)
+	};
+	let siblings = db
+		.get_dyn_fields(&id)
+		.into_iter()
+		.filter(|item| is_note(item) == is_note(&field))
+		.collect::<Vec<DbFields>>();
+	let pos = siblings.iter().position(|item| *item == field)?;
+
+	if up {
+		pos.checked_sub(1).and_then(|pos| siblings.get(pos)).copied()
+	} else {
+		siblings.get(pos + 1).copied()
+	}
+}
+
+fn move_button(
+	text: &'static str,
+	tooltip: &'static str,
+	up: bool,
+	param: &MoveButtons,
+) -> impl View {
+	let id = param.id;
+	let field = param.field;
+	let set_dyn_field_list = param.set_dyn_field_list;
+	let tooltip_signals = param.tooltip_signals;
+	let config = param.config.clone();
+
+	label(move || text)
+		.on_click_stop(move |_| {
+			tooltip_signals.hide();
+			if let Some(other) = neighbour(id, field, up, &config) {
+				let field_list: im::Vector<DbFields> =
+					config.db.write().swap_dyn_fields(&id, &field, &other).into();
+				set_dyn_field_list.set(field_list);
+			}
+		})
+		.on_event(EventListener::PointerEnter, move |_event| {
+			tooltip_signals.show(String::from(tooltip));
+			EventPropagation::Continue
+		})
+		.on_event(EventListener::PointerLeave, move |_| {
+			tooltip_signals.hide();
+			EventPropagation::Continue
+		})
+		.style(|s| {
+			s.font_size(9.0)
+				.color(C_TEXT_MAIN_INACTIVE)
+				.hover(|s| s.color(C_FOCUS).cursor(CursorStyle::Pointer))
+		})
+}
+
+pub fn move_buttons(param: MoveButtons) -> impl View {
+	v_stack((
+		move_button("▲", "Move this field up", true, &param),
+		move_button("▼", "Move this field down", false, &param),
+	))
+	.style(|s| s.items_center().margin_left(2))
+}
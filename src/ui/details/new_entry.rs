@@ -0,0 +1,427 @@
+use floem::{
+	event::{Event, EventListener},
+	keyboard::{KeyCode, PhysicalKey},
+	reactive::{create_rw_signal, RwSignal, WriteSignal},
+	style::{AlignItems, CursorStyle, Display, FlexWrap, Style},
+	view::View,
+	views::{
+		h_stack, label, list, svg, v_stack, virtual_stack, Decorators,
+		VirtualDirection, VirtualItemSize,
+	},
+	EventPropagation,
+};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use vault::{
+	config::Config,
+	db::{get_timestamp, DynamicField, DynamicFieldKind, EntryKind, NewDbEntry},
+	encryption::{generate_password, DEFAULT_PASSWORD_LENGTH},
+};
+
+use crate::ui::{
+	colors::*,
+	details::detail_view::{INPUT_LINE_WIDTH, LABEL_WIDTH, LINE_WIDTH},
+	primitives::{
+		button::text_button, input_field::input_field, tooltip::TooltipSignals,
+	},
+};
+
+// rows are keyed by the id so a replaced list never shows stale rows
+static NEXT_DRAFT_ID: AtomicUsize = AtomicUsize::new(1);
+
+#[derive(Debug, Copy, Clone)]
+pub struct DraftField {
+	pub id: usize,
+	pub title: RwSignal<String>,
+	pub value: RwSignal<String>,
+	pub kind: RwSignal<DynamicFieldKind>,
+}
+
+impl DraftField {
+	pub fn new(title: &str) -> Self {
+		Self::with_kind(title, DynamicFieldKind::default())
+	}
+
+	pub fn with_kind(title: &str, kind: DynamicFieldKind) -> Self {
+		Self {
+			id: NEXT_DRAFT_ID.fetch_add(1, Ordering::Relaxed),
+			title: create_rw_signal(String::from(title)),
+			value: create_rw_signal(String::from("")),
+			kind: create_rw_signal(kind),
+		}
+	}
+}
+
+pub struct NewEntry {
+	pub title: String,
+	pub group: Option<usize>,
+	pub all_tags: RwSignal<Vec<(String, usize)>>,
+	pub creating: RwSignal<bool>,
+	pub search_text: RwSignal<String>,
+	pub set_list: WriteSignal<im::Vector<(usize, &'static str, usize)>>,
+	pub set_active_tab: WriteSignal<usize>,
+	pub tooltip_signals: TooltipSignals,
+	pub config: Config,
+}
+
+#[derive(Clone)]
+struct SaveDraft {
+	pub kind: RwSignal<EntryKind>,
+	pub title: RwSignal<String>,
+	pub url: RwSignal<String>,
+	pub username: RwSignal<String>,
+	pub password: RwSignal<String>,
+	pub fields: RwSignal<im::Vector<DraftField>>,
+	pub tags: RwSignal<String>,
+	pub group: Option<usize>,
+	pub all_tags: RwSignal<Vec<(String, usize)>>,
+	pub error: RwSignal<String>,
+	pub creating: RwSignal<bool>,
+	pub search_text: RwSignal<String>,
+	pub set_list: WriteSignal<im::Vector<(usize, &'static str, usize)>>,
+	pub set_active_tab: WriteSignal<usize>,
+	pub config: Config,
+}
+
+fn save_draft(params: SaveDraft) {
+	let SaveDraft {
+		kind,
+		title,
+		url,
+		username,
+		password,
+		fields,
+		tags,
+		group,
+		all_tags,
+		error,
+		creating,
+		search_text,
+		set_list,
+		set_active_tab,
+		config,
+	} = params;
+
+	if title.get().trim().is_empty() {
+		error.set(String::from("The entry needs a title."));
+		return;
+	}
+
+	let fields = fields
+		.get()
+		.into_iter()
+		.filter(|field| {
+			!field.title.get().is_empty() || !field.value.get().is_empty()
+		})
+		.collect::<Vec<DraftField>>();
+
+	for field in &fields {
+		if field.title.get().is_empty() {
+			error.set(String::from("Every custom field needs a name."));
+			return;
+		}
+		if !field.value.get().is_empty()
+			&& !field.kind.get().is_valid(&field.value.get())
+		{
+			error.set(format!(
+				"\"{}\" is not a valid {} value.",
+				field.title.get(),
+				field.kind.get()
+			));
+			return;
+		}
+	}
+
+	// secure notes only keep their title, fields and tags
+	let is_login = kind.get() == EntryKind::Login;
+	let login_value = |value: RwSignal<String>| {
+		if is_login {
+			value.get()
+		} else {
+			String::from("")
+		}
+	};
+
+	let timestamp = get_timestamp();
+	let new_id = config.db.write().add_entry(NewDbEntry {
+		title: title.get(),
+		url: login_value(url),
+		username: vec![(Some(timestamp), login_value(username))],
+		password: vec![(Some(timestamp), login_value(password))],
+		fields: fields
+			.iter()
+			.enumerate()
+			.map(|(idx, field)| {
+				DynamicField::new(
+					idx + 1,
+					field.title.get(),
+					field.kind.get(),
+					field.value.get(),
+				)
+			})
+			.collect(),
+		group,
+		tags: tags.get().split(',').map(String::from).collect(),
+		kind: kind.get(),
+	});
+
+	set_list.set(config.db.read().get_list());
+	all_tags.set(config.db.read().get_all_tags());
+	creating.set(false);
+	search_text.set(String::from(""));
+	set_active_tab.set(new_id);
+}
+
+fn draft_input(
+	value: RwSignal<String>,
+	placeholder: &str,
+	params: SaveDraft,
+) -> impl View {
+	let creating = params.creating;
+
+	input_field(value)
+		.placeholder(placeholder)
+		.style(|s| s.width(INPUT_LINE_WIDTH))
+		.on_event(EventListener::KeyDown, move |event| {
+			let key = match event {
+				Event::KeyDown(k) => k.key.physical_key,
+				_ => PhysicalKey::Code(KeyCode::F35),
+			};
+
+			if key == PhysicalKey::Code(KeyCode::Escape) {
+				creating.set(false);
+			}
+
+			if key == PhysicalKey::Code(KeyCode::Enter) {
+				save_draft(params.clone());
+			}
+			EventPropagation::Continue
+		})
+}
+
+fn login_only(s: Style, kind: EntryKind) -> Style {
+	s.apply_if(kind != EntryKind::Login, |s| s.display(Display::None))
+}
+
+fn draft_label(name: &'static str) -> impl View {
+	label(move || name).style(|s| s.width(LABEL_WIDTH).padding_top(5))
+}
+
+pub fn new_entry(param: NewEntry) -> impl View {
+	let NewEntry {
+		title,
+		group,
+		all_tags,
+		creating,
+		search_text,
+		set_list,
+		set_active_tab,
+		tooltip_signals,
+		config,
+	} = param;
+
+	let password_icon = include_str!("../icons/password.svg");
+
+	let kind = create_rw_signal(EntryKind::Login);
+	let title = create_rw_signal(title);
+	let password = create_rw_signal(String::from(""));
+	let fields = create_rw_signal(im::vector![DraftField::new("Note")]);
+	let error = create_rw_signal(String::from(""));
+	let templates = config.db.read().get_templates();
+	let active_template = create_rw_signal(String::from("Login"));
+
+	let params = SaveDraft {
+		kind,
+		title,
+		url: create_rw_signal(String::from("")),
+		username: create_rw_signal(String::from("")),
+		password,
+		fields,
+		tags: create_rw_signal(String::from("")),
+		group,
+		all_tags,
+		error,
+		creating,
+		search_text,
+		set_list,
+		set_active_tab,
+		config,
+	};
+	let params_fields = params.clone();
+	let params_btn = params.clone();
+
+	v_stack((
+		h_stack((
+			svg(move || String::from(password_icon))
+				.style(|s| s.width(24).height(24)),
+			label(|| "New entry").style(|s| s.font_size(24.0)),
+			text_button(
+				move || kind.get().to_string(),
+				String::from("Switch between a login and a secure note"),
+				tooltip_signals,
+				move |_| {
+					let next = match kind.get() {
+						EntryKind::Login => EntryKind::SecureNote,
+						EntryKind::SecureNote => EntryKind::Login,
+					};
+					kind.set(next);
+
+					// the default note field becomes a multi-line note
+					if let Some(field) = fields.get().head() {
+						if field.title.get() == "Note" {
+							field.kind.set(match next {
+								EntryKind::Login => DynamicFieldKind::Secret,
+								EntryKind::SecureNote => DynamicFieldKind::Note,
+							});
+						}
+					}
+				},
+			)
+			.style(|s| s.margin_left(10)),
+		))
+		.style(|s| {
+			s.align_items(AlignItems::Center)
+				.gap(5, 0)
+				.margin_top(15)
+				.margin_bottom(10)
+		}),
+		list(
+			move || templates.clone(),
+			|template| template.title.clone(),
+			move |template| {
+				let name = template.title.clone();
+				let name_active = template.title.clone();
+
+				label(move || name.clone())
+					.on_click_stop(move |_| {
+						kind.set(template.kind);
+						fields.set(
+							template
+								.fields
+								.iter()
+								.map(|field| DraftField::with_kind(&field.title, field.kind))
+								.collect(),
+						);
+						active_template.set(template.title.clone());
+						error.set(String::from(""));
+					})
+					.style(move |s| {
+						s.font_size(11.0)
+							.padding(2)
+							.padding_left(6)
+							.padding_right(6)
+							.margin_right(4)
+							.margin_bottom(4)
+							.border_radius(8)
+							.background(C_BG_SIDE)
+							.border(1)
+							.border_color(C_BG_SIDE_BORDER)
+							.apply_if(active_template.get() == name_active, |s| {
+								s.border_color(C_FOCUS)
+							})
+							.hover(|s| s.cursor(CursorStyle::Pointer))
+					})
+			},
+		)
+		.style(|s| {
+			s.flex_row()
+				.flex_wrap(FlexWrap::Wrap)
+				.width(LINE_WIDTH)
+				.justify_center()
+				.margin_bottom(10)
+		}),
+		v_stack((
+			h_stack((
+				draft_label("Title"),
+				draft_input(title, "Title", params.clone()).request_focus(|| {}),
+			)),
+			h_stack((
+				draft_label("URL"),
+				draft_input(params.url, "https://", params.clone()),
+			))
+			.style(move |s| login_only(s, kind.get())),
+			h_stack((
+				draft_label("Username"),
+				draft_input(params.username, "Username", params.clone()),
+			))
+			.style(move |s| login_only(s, kind.get())),
+			h_stack((
+				draft_label("Password"),
+				draft_input(password, "Password", params.clone()),
+				text_button(
+					|| String::from("Generate"),
+					String::from("Generate a random password"),
+					tooltip_signals,
+					move |_| {
+						password.set(generate_password(DEFAULT_PASSWORD_LENGTH));
+					},
+				),
+			))
+			.style(move |s| login_only(s, kind.get())),
+			h_stack((
+				draft_label("Tags"),
+				draft_input(params.tags, "prod, shared", params.clone()),
+			)),
+			virtual_stack(
+				VirtualDirection::Vertical,
+				VirtualItemSize::Fixed(Box::new(|| 33.0)),
+				move || fields.get(),
+				move |field| field.id,
+				move |field| {
+					h_stack((
+						draft_input(field.title, "Field name", params_fields.clone())
+							.style(|s| s.width(LABEL_WIDTH)),
+						draft_input(field.value, "Value", params_fields.clone()),
+						text_button(
+							move || field.kind.get().to_string(),
+							String::from("Change the type of this field"),
+							tooltip_signals,
+							move |_| {
+								let pos = DynamicFieldKind::ALL
+									.iter()
+									.position(|item| *item == field.kind.get())
+									.unwrap_or(0);
+								field.kind.set(
+									DynamicFieldKind::ALL
+										[(pos + 1) % DynamicFieldKind::ALL.len()],
+								);
+							},
+						),
+					))
+					.style(|s| s.gap(4.0, 0.0).items_center())
+				},
+			)
+			.style(|s| s.flex_col().gap(0.0, 5.0)),
+		))
+		.style(|s| s.gap(0.0, 5.0)),
+		label(move || error.get()).style(|s| s.color(C_ERROR).margin_top(5)),
+		h_stack((
+			text_button(
+				|| String::from("Add field"),
+				String::from("Add a custom field"),
+				tooltip_signals,
+				move |_| {
+					fields.update(|fields| fields.push_back(DraftField::new("")));
+				},
+			),
+			text_button(
+				|| String::from("Create entry"),
+				String::from("Save the new entry to the database"),
+				tooltip_signals,
+				move |_| {
+					save_draft(params_btn.clone());
+				},
+			),
+			text_button(
+				|| String::from("Discard"),
+				String::from("Discard the new entry"),
+				tooltip_signals,
+				move |_| {
+					creating.set(false);
+				},
+			),
+		))
+		.style(|s| s.margin_top(5)),
+	))
+	.style(|s| s.padding(8.0).width_full().items_center())
+}
@@ -8,19 +8,21 @@ use floem::{
 	EventPropagation,
 };
 
-use crate::{
+use vault::{
 	config::Config,
-	db::DbFields,
-	ui::{
-		details::{
-			detail_view::{BUTTON_SLOTS_WIDTH, INPUT_LINE_WIDTH},
-			dyn_field_title_form::{dyn_field_title_form, DynFieldTitleForm},
-		},
-		primitives::{
-			button::{icon_button, IconButton},
-			input_field::input_field,
-			tooltip::TooltipSignals,
-		},
+	db::{DbFields, DynamicFieldKind},
+};
+
+use crate::ui::{
+	colors::*,
+	details::{
+		detail_view::{BUTTON_SLOTS_WIDTH, INPUT_LINE_WIDTH},
+		dyn_field_title_form::{dyn_field_title_form, DynFieldTitleForm},
+	},
+	primitives::{
+		button::{icon_button, text_button, IconButton},
+		input_field::input_field,
+		tooltip::TooltipSignals,
 	},
 };
 
@@ -28,6 +30,7 @@ struct SaveNewField {
 	pub id: usize,
 	pub title_value: RwSignal<String>,
 	pub field_value: RwSignal<String>,
+	pub kind: RwSignal<DynamicFieldKind>,
 	pub set_dyn_field_list: WriteSignal<im::Vector<DbFields>>,
 	pub tooltip_signals: TooltipSignals,
 	pub config: Config,
@@ -38,21 +41,26 @@ fn save_new_field(params: SaveNewField) {
 		id,
 		title_value,
 		field_value,
+		kind,
 		set_dyn_field_list,
 		tooltip_signals,
 		config,
 	} = params;
 
-	if !title_value.get().is_empty() && !field_value.get().is_empty() {
+	if !title_value.get().is_empty()
+		&& !field_value.get().is_empty()
+		&& kind.get().is_valid(&field_value.get())
+	{
 		let field_list: im::Vector<DbFields> = config
 			.db
 			.write()
-			.add_dyn_field(&id, title_value.get(), field_value.get())
+			.add_dyn_field(&id, title_value.get(), field_value.get(), kind.get())
 			.into();
 		set_dyn_field_list.set(field_list);
 		tooltip_signals.hide();
 		title_value.set(String::from(""));
 		field_value.set(String::from(""));
+		kind.set(DynamicFieldKind::default());
 	}
 }
 
@@ -66,6 +74,7 @@ pub fn new_field(
 	let show_minus_btn = create_rw_signal(false);
 	let title_value = create_rw_signal(String::from(""));
 	let field_value = create_rw_signal(String::from(""));
+	let kind = create_rw_signal(DynamicFieldKind::default());
 
 	let add_icon = include_str!("../icons/add.svg");
 	let minus_icon = include_str!("../icons/minus.svg");
@@ -94,6 +103,7 @@ pub fn new_field(
 						id,
 						title_value,
 						field_value,
+						kind,
 						set_dyn_field_list,
 						tooltip_signals,
 						config: config_enter_title.clone(),
@@ -101,7 +111,13 @@ pub fn new_field(
 				},
 			),
 			input_field(field_value)
-				.style(move |s| s.width(INPUT_LINE_WIDTH).padding_right(30))
+				.style(move |s| {
+					s.width(INPUT_LINE_WIDTH).padding_right(30).apply_if(
+						!field_value.get().is_empty()
+							&& !kind.get().is_valid(&field_value.get()),
+						|s| s.border_color(C_ERROR).focus(|s| s.border_color(C_ERROR)),
+					)
+				})
 				.on_event(EventListener::KeyDown, move |event| {
 					let key = match event {
 						Event::KeyDown(k) => k.key.physical_key,
@@ -118,6 +134,7 @@ pub fn new_field(
 							id,
 							title_value,
 							field_value,
+							kind,
 							set_dyn_field_list,
 							tooltip_signals,
 							config: config_enter_field.clone(),
@@ -125,24 +142,41 @@ pub fn new_field(
 					}
 					EventPropagation::Continue
 				}),
-			container(icon_button(
-				IconButton::<u8> {
-					icon: String::from(save_icon),
-					tooltip: String::from("Save to database"),
-					tooltip_signals,
-					..IconButton::default()
-				},
-				move |_| {
-					save_new_field(SaveNewField {
-						id,
-						title_value,
-						field_value,
-						set_dyn_field_list,
+			container(h_stack((
+				icon_button(
+					IconButton::<u8> {
+						icon: String::from(save_icon),
+						tooltip: String::from("Save to database"),
 						tooltip_signals,
-						config: config_btn.clone(),
-					});
-				},
-			))
+						..IconButton::default()
+					},
+					move |_| {
+						save_new_field(SaveNewField {
+							id,
+							title_value,
+							field_value,
+							kind,
+							set_dyn_field_list,
+							tooltip_signals,
+							config: config_btn.clone(),
+						});
+					},
+				),
+				text_button(
+					move || kind.get().to_string(),
+					String::from("Change the type of this field"),
+					tooltip_signals,
+					move |_| {
+						let pos = DynamicFieldKind::ALL
+							.iter()
+							.position(|item| *item == kind.get())
+							.unwrap_or(0);
+						kind.set(
+							DynamicFieldKind::ALL[(pos + 1) % DynamicFieldKind::ALL.len()],
+						);
+					},
+				),
+			)))
 			.style(move |s| {
 				s.align_items(AlignItems::Center).width(BUTTON_SLOTS_WIDTH)
 			}),
@@ -0,0 +1,404 @@
+use floem::{
+	event::{Event, EventListener},
+	keyboard::{KeyCode, PhysicalKey},
+	reactive::{create_effect, create_rw_signal, RwSignal, WriteSignal},
+	style::{AlignContent, AlignItems, Display},
+	view::View,
+	views::{container, h_stack, label, list, v_stack, Decorators},
+	EventPropagation,
+};
+
+use vault::{config::Config, db::DbFields};
+
+use crate::ui::{
+	colors::*,
+	details::{
+		button_slots::{
+			clipboard_button_slot, delete_button_slot, history_button_slot,
+			view_button_slot, DeleteButtonSlot, HistoryButtonSlot, ViewButtonSlot,
+		},
+		detail_view::{
+			BUTTON_SLOTS_WIDTH, INPUT_LINE_WIDTH, LABEL_WIDTH, LINE_WIDTH,
+			SECRET_PLACEHOLDER,
+		},
+	},
+	primitives::{
+		button::{icon_button, text_button, IconButton},
+		input_field::input_field,
+		tooltip::TooltipSignals,
+	},
+};
+
+pub struct NoteItem {
+	pub id: usize,
+	pub field: DbFields,
+	pub set_hidden_field_list: WriteSignal<im::Vector<DbFields>>,
+	pub set_dyn_field_list: WriteSignal<im::Vector<DbFields>>,
+	pub hidden_field_len: RwSignal<usize>,
+	pub tooltip_signals: TooltipSignals,
+	pub config: Config,
+}
+
+#[derive(Debug, Clone, Hash, PartialEq, Eq)]
+enum NoteLine {
+	Heading(usize, String),
+	Bullet(String),
+	Quote(String),
+	Code(String),
+	Rule,
+	Text(String),
+}
+
+// drop the inline markers we can't style inside a single label
+fn strip_inline(line: &str) -> String {
+	line.replace("**", "").replace("__", "").replace('`', "")
+}
+
+// a small line based subset of markdown, enough for headings, lists, quotes and code
+fn parse_markdown(text: &str) -> Vec<NoteLine> {
+	let mut in_code = false;
+
+	text
+		.lines()
+		.filter_map(|line| {
+			let trimmed = line.trim_start();
+			if trimmed.starts_with("```") {
+				in_code = !in_code;
+				return None;
+			}
+
+			Some(if in_code {
+				NoteLine::Code(String::from(line))
+			} else if trimmed.starts_with('#') {
+				let level = trimmed.chars().take_while(|c| *c == '#').count();
+				NoteLine::Heading(
+					level.min(3),
+					strip_inline(trimmed.trim_start_matches('#').trim()),
+				)
+			} else if let Some(item) = trimmed
+				.strip_prefix("- ")
+				.or(trimmed.strip_prefix("* "))
+				.or(trimmed.strip_prefix("+ "))
+			{
+				NoteLine::Bullet(strip_inline(item))
+			} else if let Some(quote) = trimmed.strip_prefix('>') {
+				NoteLine::Quote(strip_inline(quote.trim()))
+			} else if trimmed == "---" || trimmed == "***" {
+				NoteLine::Rule
+			} else {
+				NoteLine::Text(strip_inline(line))
+			})
+		})
+		.collect()
+}
+
+fn note_line(line: NoteLine) -> impl View {
+	let text = match &line {
+		NoteLine::Heading(_, text)
+		| NoteLine::Quote(text)
+		| NoteLine::Code(text)
+		| NoteLine::Text(text) => text.clone(),
+		NoteLine::Bullet(text) => format!("•  {}", text),
+		NoteLine::Rule => String::from(""),
+	};
+
+	label(move || text.clone()).style(move |s| match &line {
+		NoteLine::Heading(level, _) => s
+			.font_size(match *level {
+				1 => 20.0,
+				2 => 17.0,
+				_ => 15.0,
+			})
+			.margin_top(4)
+			.margin_bottom(2),
+		NoteLine::Bullet(_) => s.padding_left(8),
+		NoteLine::Quote(_) => s
+			.color(C_TEXT_MAIN_INACTIVE)
+			.padding_left(8)
+			.border_left(2)
+			.border_color(C_BG_SIDE_BORDER),
+		NoteLine::Code(_) => s
+			.font_family(String::from("monospace"))
+			.background(C_BG_SIDE)
+			.padding_left(6)
+			.width_full(),
+		NoteLine::Rule => s
+			.width_full()
+			.height(1)
+			.margin_top(4)
+			.margin_bottom(4)
+			.border_bottom(1)
+			.border_color(C_BG_MAIN_BORDER),
+		NoteLine::Text(_) => s,
+	})
+}
+
+fn note_editor(
+	lines: RwSignal<im::Vector<(usize, RwSignal<String>)>>,
+	focus_line: RwSignal<Option<usize>>,
+	on_cancel: impl Fn() + Copy + 'static,
+) -> impl View {
+	list(
+		move || lines.get(),
+		|(key, _)| *key,
+		move |(key, value)| {
+			let input = input_field(value);
+			let input_id = input.id();
+			let was_empty = create_rw_signal(value.get_untracked().is_empty());
+
+			create_effect(move |_| {
+				if focus_line.get() == Some(key) {
+					input_id.request_focus();
+				}
+			});
+
+			let neighbour = move |offset: isize| {
+				let all = lines.get();
+				all
+					.iter()
+					.position(|(item, _)| *item == key)
+					.and_then(|pos| all.get((pos as isize + offset).max(0) as usize))
+					.map(|(item, _)| *item)
+			};
+
+			input
+				.style(|s| s.width_full())
+				.on_event(EventListener::KeyDown, move |event| {
+					let key_code = match event {
+						Event::KeyDown(k) => k.key.physical_key,
+						_ => PhysicalKey::Code(KeyCode::F35),
+					};
+
+					match key_code {
+						PhysicalKey::Code(KeyCode::Escape) => on_cancel(),
+						PhysicalKey::Code(KeyCode::Enter) => {
+							let new_key =
+								lines.get().iter().map(|(item, _)| *item).max().unwrap_or(0)
+									+ 1;
+							lines.update(|lines| {
+								let pos = lines
+									.iter()
+									.position(|(item, _)| *item == key)
+									.map(|pos| pos + 1)
+									.unwrap_or(lines.len());
+								lines
+									.insert(pos, (new_key, create_rw_signal(String::from(""))));
+							});
+							focus_line.set(Some(new_key));
+						}
+						PhysicalKey::Code(KeyCode::Backspace)
+							if was_empty.get() && lines.get().len() > 1 =>
+						{
+							let next =
+								neighbour(-1).filter(|item| *item != key).or(neighbour(1));
+							lines.update(|lines| lines.retain(|(item, _)| *item != key));
+							focus_line.set(next);
+						}
+						PhysicalKey::Code(KeyCode::ArrowUp) => {
+							focus_line.set(neighbour(-1));
+						}
+						PhysicalKey::Code(KeyCode::ArrowDown) => {
+							focus_line.set(neighbour(1));
+						}
+						_ => {}
+					}
+					EventPropagation::Continue
+				})
+				.on_event(EventListener::KeyUp, move |_| {
+					was_empty.set(value.get().is_empty());
+					EventPropagation::Continue
+				})
+		},
+	)
+	.style(|s| s.flex_col().width_full().gap(0.0, 2.0))
+}
+
+pub fn note_item(param: NoteItem) -> impl View {
+	let NoteItem {
+		id,
+		field,
+		set_hidden_field_list,
+		set_dyn_field_list,
+		hidden_field_len,
+		tooltip_signals,
+		config,
+	} = param;
+
+	let edit_icon = include_str!("../icons/edit.svg");
+	let save_icon = include_str!("../icons/save.svg");
+
+	let field_title = config.db.read().get_name_of_dyn_field(&id, &field);
+	let dates = create_rw_signal(config.db.read().get_history_dates(&id, &field));
+	let field_value = create_rw_signal(String::from(SECRET_PLACEHOLDER));
+	let view_button_switch = create_rw_signal(false);
+	let edit_button_switch = create_rw_signal(false);
+	let markdown = create_rw_signal(false);
+	let lines = create_rw_signal(im::Vector::new());
+	let focus_line = create_rw_signal(None);
+
+	// versions restored from the history window change the current value
+	let config_restored = config.clone();
+	create_effect(move |prev: Option<()>| {
+		dates.track();
+		if prev.is_some() && view_button_switch.get_untracked() {
+			field_value.set(config_restored.db.read().get_last_by_field(&id, &field));
+		}
+	});
+
+	let config_edit = config.clone();
+	let config_viewbtn = config.clone();
+	let config_history = config.clone();
+	let config_deletebtn = config.clone();
+
+	let on_cancel = move || {
+		edit_button_switch.set(false);
+		lines.set(im::Vector::new());
+	};
+
+	let title = field_title.clone();
+
+	v_stack((
+		h_stack((
+			container(label(move || title.clone()))
+				.style(|s| s.width(LABEL_WIDTH).justify_content(AlignContent::End)),
+			h_stack((
+				label(move || {
+					if edit_button_switch.get() {
+						String::from("Editing")
+					} else if view_button_switch.get() {
+						format!("{} lines", field_value.get().lines().count())
+					} else {
+						String::from(SECRET_PLACEHOLDER)
+					}
+				})
+				.style(|s| s.flex_grow(1.0)),
+				text_button(
+					move || {
+						if markdown.get() {
+							String::from("Plain text")
+						} else {
+							String::from("Markdown")
+						}
+					},
+					String::from("Switch how the note is displayed"),
+					tooltip_signals,
+					move |_| {
+						markdown.set(!markdown.get());
+					},
+				),
+			))
+			.style(|s| {
+				s.width(INPUT_LINE_WIDTH)
+					.items_center()
+					.padding_left(6)
+					.border_bottom(1)
+					.border_color(C_TEXT_TOP)
+			}),
+			h_stack((
+				icon_button(
+					IconButton {
+						icon: String::from(edit_icon),
+						icon2: Some(String::from(save_icon)),
+						bubble: None::<RwSignal<Vec<u8>>>,
+						tooltip: String::from("Edit this note"),
+						tooltip2: Some(String::from("Save to database")),
+						switch: Some(edit_button_switch),
+						tooltip_signals,
+					},
+					move |_| {
+						if edit_button_switch.get() {
+							let text = config_edit.db.read().get_last_by_field(&id, &field);
+							let mut new_lines = im::Vector::new();
+							for (key, line) in text.split('\n').enumerate() {
+								new_lines
+									.push_back((key, create_rw_signal(String::from(line))));
+							}
+							lines.set(new_lines);
+							focus_line.set(Some(0));
+						} else {
+							let text = lines
+								.get()
+								.iter()
+								.map(|(_, line)| line.get())
+								.collect::<Vec<String>>()
+								.join("\n");
+							if config_edit.db.read().get_last_by_field(&id, &field) != text {
+								config_edit.db.write().edit_field(id, &field, text.clone());
+								dates.set(config_edit.db.read().get_history_dates(&id, &field));
+							}
+							if view_button_switch.get() {
+								field_value.set(text);
+							}
+							lines.set(im::Vector::new());
+						}
+					},
+				),
+				clipboard_button_slot(tooltip_signals, move || {
+					let value = config.db.read().get_last_by_field(&id, &field);
+					config.db.write().mark_used(&id);
+					value
+				}),
+				view_button_slot(
+					ViewButtonSlot {
+						switch: view_button_switch,
+						is_secret: true,
+						tooltip_signals,
+						field_value,
+					},
+					move || config_viewbtn.db.read().get_last_by_field(&id, &field),
+				),
+				history_button_slot(HistoryButtonSlot {
+					id,
+					field,
+					dates,
+					is_secret: true,
+					field_title,
+					tooltip_signals,
+					config: config_history,
+				}),
+				delete_button_slot(DeleteButtonSlot {
+					id,
+					field,
+					set_hidden_field_list,
+					set_dyn_field_list,
+					hidden_field_len,
+					is_dyn_field: true,
+					is_hidden: false,
+					tooltip_signals,
+					config: config_deletebtn,
+				}),
+			))
+			.style(|s| s.align_items(AlignItems::Center).width(BUTTON_SLOTS_WIDTH)),
+		))
+		.style(|s| s.align_items(AlignItems::Center).gap(4.0, 0.0)),
+		container(note_editor(lines, focus_line, on_cancel)).style(move |s| {
+			s.margin_left(LABEL_WIDTH + 4.0)
+				.width(INPUT_LINE_WIDTH)
+				.display(Display::None)
+				.apply_if(edit_button_switch.get(), |s| s.display(Display::Flex))
+		}),
+		list(
+			move || {
+				let text = field_value.get();
+				let note_lines = if markdown.get() {
+					parse_markdown(&text)
+				} else {
+					text.lines().map(|line| NoteLine::Text(String::from(line))).collect()
+				};
+				note_lines.into_iter().enumerate()
+			},
+			|item| item.clone(),
+			|(_, line)| note_line(line),
+		)
+		.style(move |s| {
+			s.flex_col()
+				.margin_left(LABEL_WIDTH + 10.0)
+				.width(INPUT_LINE_WIDTH)
+				.display(Display::None)
+				.apply_if(view_button_switch.get() && !edit_button_switch.get(), |s| {
+					s.display(Display::Flex)
+				})
+		}),
+	))
+	.style(|s| s.width(LINE_WIDTH).gap(0.0, 5.0).padding_bottom(5))
+}
@@ -0,0 +1,150 @@
+use floem::{
+	event::{Event, EventListener},
+	keyboard::{KeyCode, PhysicalKey},
+	reactive::{create_rw_signal, RwSignal},
+	style::{AlignContent, AlignItems, CursorStyle, FlexWrap},
+	view::View,
+	views::{container, h_stack, label, list, v_stack, Decorators},
+	EventPropagation,
+};
+
+use vault::config::Config;
+
+use crate::ui::{
+	colors::*,
+	details::detail_view::{
+		BUTTON_SLOTS_WIDTH, INPUT_LINE_WIDTH, LABEL_WIDTH, LINE_WIDTH,
+	},
+	primitives::{
+		button::{icon_button, IconButton},
+		input_field::input_field,
+		tooltip::TooltipSignals,
+	},
+};
+
+struct SaveTag {
+	pub id: usize,
+	pub tags: RwSignal<Vec<String>>,
+	pub tag_value: RwSignal<String>,
+	pub all_tags: RwSignal<Vec<(String, usize)>>,
+	pub config: Config,
+}
+
+fn save_tag(params: SaveTag) {
+	let SaveTag {
+		id,
+		tags,
+		tag_value,
+		all_tags,
+		config,
+	} = params;
+
+	if !tag_value.get().trim().is_empty() {
+		tags.set(config.db.write().add_tag(&id, tag_value.get()));
+		all_tags.set(config.db.read().get_all_tags());
+		tag_value.set(String::from(""));
+	}
+}
+
+pub fn tag_list(
+	id: usize,
+	all_tags: RwSignal<Vec<(String, usize)>>,
+	tooltip_signals: TooltipSignals,
+	config: Config,
+) -> impl View {
+	let tags = create_rw_signal(config.db.read().get_tags(&id));
+	let tag_value = create_rw_signal(String::from(""));
+
+	let add_icon = include_str!("../icons/add.svg");
+
+	let config_remove = config.clone();
+	let config_enter = config.clone();
+
+	h_stack((
+		container(label(|| "Tags")).style(|s| {
+			s.width(LABEL_WIDTH).justify_content(AlignContent::End).padding_top(5)
+		}),
+		v_stack((
+			list(
+				move || tags.get(),
+				move |tag| tag.clone(),
+				move |tag| {
+					let tag_remove = tag.clone();
+					let config_remove = config_remove.clone();
+
+					h_stack((
+						label(move || tag.clone()).style(|s| s.font_size(11.0)),
+						label(|| "×")
+							.on_click_stop(move |_| {
+								tags.set(config_remove.db.write().remove_tag(&id, &tag_remove));
+								all_tags.set(config_remove.db.read().get_all_tags());
+							})
+							.style(|s| {
+								s.font_size(11.0)
+									.margin_left(4)
+									.color(C_TEXT_MAIN_INACTIVE)
+									.hover(|s| s.color(C_ERROR).cursor(CursorStyle::Pointer))
+							}),
+					))
+					.style(|s| {
+						s.items_center()
+							.padding(2)
+							.padding_left(6)
+							.padding_right(6)
+							.margin_right(4)
+							.margin_bottom(4)
+							.border_radius(8)
+							.background(C_BG_SIDE)
+							.border(1)
+							.border_color(C_BG_SIDE_BORDER)
+					})
+				},
+			)
+			.style(|s| s.flex_row().flex_wrap(FlexWrap::Wrap)),
+			input_field(tag_value)
+				.placeholder("Add a tag")
+				.style(|s| s.width_full())
+				.on_event(EventListener::KeyDown, move |event| {
+					let key = match event {
+						Event::KeyDown(k) => k.key.physical_key,
+						_ => PhysicalKey::Code(KeyCode::F35),
+					};
+
+					if key == PhysicalKey::Code(KeyCode::Escape) {
+						tag_value.set(String::from(""));
+					}
+
+					if key == PhysicalKey::Code(KeyCode::Enter) {
+						save_tag(SaveTag {
+							id,
+							tags,
+							tag_value,
+							all_tags,
+							config: config_enter.clone(),
+						});
+					}
+					EventPropagation::Continue
+				}),
+		))
+		.style(|s| s.width(INPUT_LINE_WIDTH)),
+		container(icon_button(
+			IconButton::<u8> {
+				icon: String::from(add_icon),
+				tooltip: String::from("Add tag"),
+				tooltip_signals,
+				..IconButton::default()
+			},
+			move |_| {
+				save_tag(SaveTag {
+					id,
+					tags,
+					tag_value,
+					all_tags,
+					config: config.clone(),
+				});
+			},
+		))
+		.style(|s| s.align_items(AlignItems::Center).width(BUTTON_SLOTS_WIDTH)),
+	))
+	.style(|s| s.align_items(AlignItems::End).gap(4.0, 0.0).width(LINE_WIDTH))
+}
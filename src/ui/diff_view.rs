@@ -0,0 +1,88 @@
+use chrono::{DateTime, Local, Utc};
+use floem::{
+	style::FlexWrap,
+	view::View,
+	views::{h_stack, label, list, scroll, v_stack, Decorators},
+};
+
+use vault::diff::{side_by_side, Change, Span};
+
+use crate::ui::{colors::*, primitives::styles};
+
+fn format_date(date: Option<u64>) -> String {
+	match date.and_then(|date| DateTime::<Utc>::from_timestamp(date as i64, 0)) {
+		Some(datetime_utc) => {
+			let datetime_local: DateTime<Local> = datetime_utc.with_timezone(&Local);
+			datetime_local.format("%v %R").to_string()
+		}
+		None => String::from("unknown date"),
+	}
+}
+
+fn diff_side(spans: Vec<Span>) -> impl View {
+	list(
+		move || spans.clone().into_iter().enumerate(),
+		|(idx, _)| *idx,
+		|(_, span)| {
+			let text = span.text.clone();
+			label(move || text.clone()).style(move |s| match span.change {
+				Change::Same => s,
+				Change::Added => s.background(C_DIFF_ADDED),
+				Change::Removed => s.background(C_DIFF_REMOVED),
+			})
+		},
+	)
+	.style(|s| {
+		s.flex_row()
+			.flex_wrap(FlexWrap::Wrap)
+			.width_pct(50.0)
+			.min_height(16)
+			.padding_horiz(5)
+	})
+}
+
+pub fn diff_view(
+	old: (Option<u64>, String),
+	new: (Option<u64>, String),
+) -> impl View {
+	let old_date = format_date(old.0);
+	let new_date = format_date(new.0);
+	let rows = side_by_side(&old.1, &new.1);
+
+	v_stack((
+		h_stack((
+			label(move || old_date.clone()).style(|s| s.width_pct(50.0)),
+			label(move || new_date.clone()).style(|s| s.width_pct(50.0)),
+		))
+		.style(|s| {
+			s.width_full()
+				.padding(5)
+				.color(C_TEXT_SIDE_INACTIVE)
+				.font_size(10.0)
+				.border_bottom(1)
+				.border_color(C_BG_MAIN_BORDER)
+		}),
+		scroll(
+			list(
+				move || rows.clone().into_iter().enumerate(),
+				|(idx, _)| *idx,
+				|(_, row)| {
+					h_stack((
+						diff_side(row.left)
+							.style(|s| s.border_right(1).border_color(C_BG_MAIN_BORDER)),
+						diff_side(row.right),
+					))
+					.style(|s| s.width_full())
+				},
+			)
+			.style(|s| s.flex_col().width_full()),
+		)
+		.style(|s| {
+			s.width_full()
+				.flex_grow(1.0)
+				.min_height(0)
+				.class(scroll::Handle, styles::scrollbar_styles)
+		}),
+	))
+	.style(|s| s.width_full().height_full().background(C_BG_MAIN))
+}
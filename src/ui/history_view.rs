@@ -1,56 +1,113 @@
 use chrono::{DateTime, Local, Utc};
 use floem::{
 	event::{Event, EventListener},
-	reactive::{create_rw_signal, create_signal},
+	kurbo::Size,
+	reactive::{create_rw_signal, RwSignal},
+	style::Display,
 	view::View,
 	views::virtual_stack,
 	views::{
-		container, dyn_container, h_stack, label, scroll, Decorators,
+		container, dyn_container, h_stack, label, scroll, v_stack, Decorators,
 		VirtualDirection, VirtualItemSize,
 	},
 	EventPropagation,
 };
 
-use crate::{
-	config::Config,
-	db::DbFields,
-	ui::{
-		colors::*,
-		details::{
-			button_slots::{clipboard_button_slot, view_button_slot, ViewButtonSlot},
-			detail_view::SECRET_PLACEHOLDER,
-		},
-		primitives::{
-			styles,
-			tooltip::{tooltip_view, TooltipSignals},
-		},
+use vault::{config::Config, db::DbFields};
+
+use crate::ui::{
+	colors::*,
+	database_view::{parse_retention, retention_inputs, to_inputs},
+	details::{
+		button_slots::{clipboard_button_slot, view_button_slot, ViewButtonSlot},
+		detail_view::SECRET_PLACEHOLDER,
+	},
+	diff_view::diff_view,
+	primitives::{
+		button::{icon_button, text_button, IconButton},
+		styles,
+		tooltip::{tooltip_view, TooltipSignals},
 	},
+	window_management::{make_field_path, opening_window, WindowSpec},
 };
 
 const HISTORY_LINE_HEIGHT: f64 = 31.0;
 
-fn history_line(
+struct HistoryLine {
 	idx: usize,
 	id: usize,
 	field: DbFields,
-	date: u64,
+	date: Option<u64>,
+	dates: RwSignal<Vec<(usize, Option<u64>)>>,
+	compare: RwSignal<Vec<usize>>,
+	is_secret: bool,
 	tooltip_signals: TooltipSignals,
 	config: Config,
-) -> impl View {
+}
+
+fn history_line(param: HistoryLine) -> impl View {
+	let HistoryLine {
+		idx,
+		id,
+		field,
+		date,
+		dates,
+		compare,
+		is_secret,
+		tooltip_signals,
+		config,
+	} = param;
+	let revert_icon = include_str!("./icons/revert.svg");
+
 	let view_button_switch = create_rw_signal(false);
-	let field_value = create_rw_signal(String::from(SECRET_PLACEHOLDER));
+	let field_value = if is_secret {
+		create_rw_signal(String::from(SECRET_PLACEHOLDER))
+	} else {
+		create_rw_signal(config.db.read().get_n_by_field(&id, &field, idx))
+	};
 
 	let config_viewbtn = config.clone();
+	let config_restore = config.clone();
 
-	let datetime_utc: DateTime<Utc> =
-		DateTime::from_timestamp(date as i64, 0).unwrap();
-	let datetime_local: DateTime<Local> = datetime_utc.with_timezone(&Local);
+	let (short_date, long_date) = match date
+		.and_then(|date| DateTime::<Utc>::from_timestamp(date as i64, 0))
+	{
+		Some(datetime_utc) => {
+			let datetime_local: DateTime<Local> = datetime_utc.with_timezone(&Local);
+			(datetime_local.format("%v").to_string(), datetime_local.to_rfc2822())
+		}
+		None => (
+			String::from("unknown"),
+			String::from("Written before dates were recorded"),
+		),
+	};
 
 	h_stack((
-		label(move || datetime_local.format("%v"))
+		label(move || {
+			if compare.get().contains(&idx) {
+				"☑"
+			} else {
+				"☐"
+			}
+		})
+		.style(|s| s.color(C_TEXT_SIDE_INACTIVE))
+		.on_click_stop(move |_| {
+			compare.update(|selected| {
+				if let Some(pos) = selected.iter().position(|item| *item == idx) {
+					selected.remove(pos);
+				} else {
+					// only two versions can be compared, drop the oldest selection
+					if selected.len() == 2 {
+						selected.remove(0);
+					}
+					selected.push(idx);
+				}
+			});
+		}),
+		label(move || short_date.clone())
 			.style(|s| s.color(C_TEXT_SIDE_INACTIVE).font_size(9.0))
 			.on_event(EventListener::PointerEnter, move |_event| {
-				tooltip_signals.show(datetime_local.to_rfc2822());
+				tooltip_signals.show(long_date.clone());
 				EventPropagation::Continue
 			})
 			.on_event(EventListener::PointerLeave, move |_| {
@@ -80,15 +137,31 @@ fn history_line(
 		view_button_slot(
 			ViewButtonSlot {
 				switch: view_button_switch,
-				is_secret: true,
+				is_secret,
 				tooltip_signals,
 				field_value,
 			},
 			move || config_viewbtn.db.read().get_n_by_field(&id, &field, idx),
 		),
 		clipboard_button_slot(tooltip_signals, move || {
+			config.db.write().mark_used(&id);
 			config.db.read().get_n_by_field(&id, &field, idx)
 		}),
+		icon_button(
+			IconButton::<u8> {
+				icon: String::from(revert_icon),
+				tooltip: String::from("Restore this version"),
+				tooltip_signals,
+				..IconButton::default()
+			},
+			move |_| {
+				tooltip_signals.hide();
+				config_restore.db.write().restore_field(&id, &field, idx);
+				compare.set(Vec::new());
+				dates.set(config_restore.db.read().get_history_dates(&id, &field));
+			},
+		)
+		.style(move |s| s.apply_if(idx == 0, |s| s.display(Display::None))),
 	))
 	.style(move |s| {
 		s.flex()
@@ -111,32 +184,164 @@ fn history_line(
 pub fn history_view(
 	id: usize,
 	field: DbFields,
-	dates: Vec<(usize, u64)>,
+	field_title: String,
+	dates: RwSignal<Vec<(usize, Option<u64>)>>,
+	is_secret: bool,
 	config: Config,
 ) -> impl View {
-	let long_list: im::Vector<(usize, u64)> = dates.into();
-	let (long_list, _set_long_list) = create_signal(long_list);
-
 	let tooltip_signals = TooltipSignals::new();
 
-	let history_view = h_stack((
-		scroll(
-			virtual_stack(
-				VirtualDirection::Vertical,
-				VirtualItemSize::Fixed(Box::new(|| HISTORY_LINE_HEIGHT)),
-				move || long_list.get(),
-				move |item| *item,
-				move |(idx, date)| {
-					history_line(idx, id, field, date, tooltip_signals, config.clone())
+	let field_retention = config.db.read().get_field_retention(&id, &field);
+	let (versions, days) = to_inputs(field_retention);
+	let versions = create_rw_signal(versions);
+	let days = create_rw_signal(days);
+	let retention_label = create_rw_signal(match field_retention {
+		Some(retention) => retention.to_string(),
+		None => String::from("Using the vault retention"),
+	});
+
+	let compare = create_rw_signal(Vec::new());
+
+	let config_retention = config.clone();
+	let config_default = config.clone();
+	let config_compare = config.clone();
+
+	let retention_form = v_stack((
+		label(move || retention_label.get())
+			.style(|s| s.color(C_TEXT_SIDE_INACTIVE).font_size(10.0)),
+		h_stack((
+			retention_inputs(versions, days),
+			text_button(
+				|| String::from("Save"),
+				String::from("Use this retention for the field"),
+				tooltip_signals,
+				move |_| match parse_retention(&versions.get(), &days.get()) {
+					Ok(retention) => {
+						config_retention.db.write().set_field_retention(
+							&id,
+							&field,
+							Some(retention),
+						);
+						retention_label.set(retention.to_string());
+					}
+					Err(err) => retention_label.set(err),
 				},
-			)
-			.style(|s| s.flex_col().flex_grow(1.0)),
+			),
+			text_button(
+				|| String::from("Default"),
+				String::from("Use the vault retention for the field"),
+				tooltip_signals,
+				move |_| {
+					config_default.db.write().set_field_retention(&id, &field, None);
+					let (default_versions, default_days) = to_inputs(None);
+					versions.set(default_versions);
+					days.set(default_days);
+					retention_label.set(String::from("Using the vault retention"));
+				},
+			),
+		))
+		.style(|s| s.items_center().gap(4.0, 0.0)),
+	))
+	.style(|s| {
+		s.padding(5).font_size(10.0).border_bottom(1).border_color(C_BG_MAIN_BORDER)
+	});
+
+	let compare_bar = h_stack((
+		label(move || match compare.get().len() {
+			2 => String::from("Compare the selected versions"),
+			_ => String::from("Select two versions to compare"),
+		})
+		.style(|s| s.flex_grow(1.0).color(C_TEXT_SIDE_INACTIVE)),
+		text_button(
+			|| String::from("Compare"),
+			String::from("Show the changes between the selected versions"),
+			tooltip_signals,
+			move |_| {
+				let selected = compare.get();
+				if selected.len() != 2 {
+					return;
+				}
+				// a higher index is an older version
+				let older = selected[0].max(selected[1]);
+				let newer = selected[0].min(selected[1]);
+				let all_dates = dates.get();
+				let date_of = |n: usize| {
+					all_dates
+						.iter()
+						.find(|(idx, _)| *idx == n)
+						.and_then(|(_, date)| *date)
+				};
+				let old = (
+					date_of(older),
+					config_compare.db.read().get_n_by_field(&id, &field, older),
+				);
+				let new = (
+					date_of(newer),
+					config_compare.db.read().get_n_by_field(&id, &field, newer),
+				);
+
+				opening_window(
+					move || diff_view(old.clone(), new.clone()),
+					WindowSpec {
+						id: format!("{}-diff", make_field_path(id, &field)),
+						title: format!("{} Field Diff", field_title),
+					},
+					Size::new(600.0, 400.0),
+					|| {},
+				);
+			},
 		)
-		.style(|s| {
-			s.width_full()
-				.height_full()
-				.class(scroll::Handle, styles::scrollbar_styles)
+		.style(move |s| {
+			s.apply_if(compare.get().len() != 2, |s| s.color(C_TEXT_SIDE_INACTIVE))
 		}),
+	))
+	.style(|s| {
+		s.padding(5)
+			.font_size(10.0)
+			.items_center()
+			.border_bottom(1)
+			.border_color(C_BG_MAIN_BORDER)
+	});
+
+	let history_view = h_stack((
+		v_stack((
+			retention_form,
+			compare_bar,
+			scroll(
+				virtual_stack(
+					VirtualDirection::Vertical,
+					VirtualItemSize::Fixed(Box::new(|| HISTORY_LINE_HEIGHT)),
+					move || {
+						dates
+							.get()
+							.into_iter()
+							.collect::<im::Vector<(usize, Option<u64>)>>()
+					},
+					move |item| *item,
+					move |(idx, date)| {
+						history_line(HistoryLine {
+							idx,
+							id,
+							field,
+							date,
+							dates,
+							compare,
+							is_secret,
+							tooltip_signals,
+							config: config.clone(),
+						})
+					},
+				)
+				.style(|s| s.flex_col().flex_grow(1.0)),
+			)
+			.style(|s| {
+				s.width_full()
+					.flex_grow(1.0)
+					.min_height(0)
+					.class(scroll::Handle, styles::scrollbar_styles)
+			}),
+		))
+		.style(|s| s.width_full().height_full()),
 		tooltip_view(tooltip_signals),
 	))
 	.style(|s| s.width_full().height_full())
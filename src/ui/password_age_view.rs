@@ -0,0 +1,114 @@
+use floem::{
+	reactive::{create_rw_signal, RwSignal},
+	style::{AlignItems, Display},
+	view::View,
+	views::{h_stack, label, list, v_stack, Decorators},
+};
+
+use vault::{
+	config::Config,
+	db::{get_timestamp, PasswordAge},
+};
+
+use crate::ui::{
+	colors::*,
+	primitives::{
+		button::text_button, input_field::input_field, tooltip::TooltipSignals,
+	},
+};
+
+const REPORT_WIDTH: f64 = 400.0;
+
+// an empty input turns the policy off
+pub fn parse_max_age(days: &str) -> Result<Option<u64>, String> {
+	match days.trim() {
+		"" => Ok(None),
+		value => match value.parse::<u64>() {
+			Ok(0) | Err(_) => {
+				Err(String::from("The days need to be a number above 0."))
+			}
+			Ok(value) => Ok(Some(value)),
+		},
+	}
+}
+
+fn age_line(age: PasswordAge) -> impl View {
+	let title = age.title.clone();
+	let is_stale = age.is_stale;
+
+	h_stack((
+		label(move || title.clone()).style(|s| s.flex_grow(1.0)),
+		label(move || match age.days {
+			Some(days) => format!("{} days", days),
+			None => String::from("unknown"),
+		})
+		.style(|s| s.color(C_TEXT_MAIN_INACTIVE).font_size(11.0)),
+		label(|| "rotate").style(move |s| {
+			s.font_size(9.0)
+				.padding_horiz(3)
+				.border_radius(2)
+				.color(C_BG_MAIN)
+				.background(C_ERROR)
+				.apply_if(!is_stale, |s| s.display(Display::None))
+		}),
+	))
+	.style(|s| {
+		s.items_center()
+			.gap(6.0, 0.0)
+			.width(REPORT_WIDTH)
+			.padding(6)
+			.border_bottom(1)
+			.border_color(C_BG_MAIN_BORDER)
+	})
+}
+
+pub fn password_age_view(
+	tooltip_signals: TooltipSignals,
+	config: Config,
+) -> impl View {
+	let ages: RwSignal<Vec<PasswordAge>> =
+		create_rw_signal(config.db.read().get_password_ages(get_timestamp()));
+	let max_age = create_rw_signal(
+		config
+			.db
+			.read()
+			.get_password_max_age()
+			.map(|days| days.to_string())
+			.unwrap_or_default(),
+	);
+	let error = create_rw_signal(String::from(""));
+
+	v_stack((
+		label(|| "Password rotation").style(|s| s.font_size(16.0)),
+		label(|| "Passwords older than this are marked in the sidebar.")
+			.style(|s| s.color(C_TEXT_MAIN_INACTIVE).margin_bottom(5)),
+		h_stack((
+			label(|| "Change passwords every"),
+			input_field(max_age).placeholder("never").style(|s| s.width(45)),
+			label(|| "days"),
+			text_button(
+				|| String::from("Save"),
+				String::from("Entries can override this in their details"),
+				tooltip_signals,
+				move |_| match parse_max_age(&max_age.get()) {
+					Ok(days) => {
+						config.db.write().set_password_max_age(days);
+						ages.set(config.db.read().get_password_ages(get_timestamp()));
+						error.set(String::from(""));
+					}
+					Err(err) => error.set(err),
+				},
+			),
+		))
+		.style(|s| s.align_items(AlignItems::Center).gap(4.0, 0.0)),
+		label(move || error.get()).style(move |s| {
+			s.color(C_ERROR)
+				.display(Display::None)
+				.apply_if(!error.get().is_empty(), |s| s.display(Display::Flex))
+		}),
+		label(|| "Passwords by age").style(|s| s.font_size(14.0).margin_top(10)),
+		list(move || ages.get(), |age| age.clone(), age_line)
+			.style(|s| s.flex_col()),
+	))
+	.style(|s| s.padding(8.0).gap(0.0, 5.0))
+}
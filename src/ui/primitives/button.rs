@@ -238,3 +238,53 @@ pub fn icon_button<T: Clone + 'static>(
 		EventPropagation::Continue
 	})
 }
+
+pub fn text_button(
+	text: impl Fn() -> String + 'static,
+	tooltip: String,
+	tooltip_signals: TooltipSignals,
+	on_click: impl Fn(&Event) + 'static,
+) -> impl View {
+	label(text)
+		.keyboard_navigatable()
+		.style(|s| {
+			s.padding(3)
+				.padding_left(6)
+				.padding_right(6)
+				.margin(3)
+				.margin_left(0)
+				.margin_right(1.5)
+				.font_size(11.0)
+				.border(1)
+				.border_color(C_TEXT_TOP)
+				.border_radius(2)
+				.box_shadow_blur(0.3)
+				.box_shadow_color(C_SHADOW_3)
+				.box_shadow_spread(0)
+				.box_shadow_h_offset(2)
+				.box_shadow_v_offset(2)
+				.background(C_BG_MAIN)
+				.hover(|s| {
+					s.background(C_BG_SIDE_SELECTED.with_alpha_factor(0.6))
+						.cursor(CursorStyle::Pointer)
+				})
+				.active(|s| {
+					s.background(C_BG_SIDE_SELECTED)
+						.box_shadow_h_offset(0)
+						.box_shadow_v_offset(0)
+				})
+				.focus_visible(|s| s.outline(1).outline_color(C_FOCUS))
+		})
+		.on_event(EventListener::PointerEnter, move |_event| {
+			tooltip_signals.show(tooltip.clone());
+			EventPropagation::Continue
+		})
+		.on_event(EventListener::PointerLeave, move |_| {
+			tooltip_signals.hide();
+			EventPropagation::Continue
+		})
+		.on_click(move |event| {
+			on_click(event);
+			EventPropagation::Continue
+		})
+}
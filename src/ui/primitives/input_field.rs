@@ -1,24 +1,54 @@
 use floem::{
+	event::EventListener,
+	id::Id,
 	reactive::RwSignal,
 	views::{Decorators, TextInput},
 	widgets::{text_input, PlaceholderTextClass},
+	EventPropagation,
 };
+use std::cell::Cell;
 
 use crate::ui::colors::*;
 
+thread_local! {
+	// the input that has the keyboard focus so shortcuts can leave its keys alone
+	static FOCUSED_INPUT: Cell<Option<Id>> = const { Cell::new(None) };
+}
+
+pub fn focused_input() -> Option<Id> {
+	FOCUSED_INPUT.with(|focused| focused.get())
+}
+
 pub fn input_field(value: RwSignal<String>) -> TextInput {
-	text_input(value).style(move |s| {
-		s.padding_left(5)
-			.padding_bottom(5)
-			.padding_top(4)
-			.border_radius(2)
-			.border_color(C_TEXT_TOP)
-			.cursor_color(C_FOCUS.with_alpha_factor(0.5))
-			.hover(|s| s.background(C_FOCUS.with_alpha_factor(0.05)))
-			.focus(|s| s.border_color(C_FOCUS).outline_color(C_FOCUS))
-			.focus_visible(|s| s.outline(1))
-			.class(PlaceholderTextClass, |s| {
-				s.color(C_TEXT_MAIN.with_alpha_factor(0.5))
-			})
-	})
+	let input = text_input(value);
+	let input_id = input.id();
+
+	input
+		.on_event(EventListener::FocusGained, move |_| {
+			FOCUSED_INPUT.with(|focused| focused.set(Some(input_id)));
+			EventPropagation::Continue
+		})
+		.on_event(EventListener::FocusLost, move |_| {
+			// another input may have taken the focus already
+			FOCUSED_INPUT.with(|focused| {
+				if focused.get() == Some(input_id) {
+					focused.set(None);
+				}
+			});
+			EventPropagation::Continue
+		})
+		.style(move |s| {
+			s.padding_left(5)
+				.padding_bottom(5)
+				.padding_top(4)
+				.border_radius(2)
+				.border_color(C_TEXT_TOP)
+				.cursor_color(C_FOCUS.with_alpha_factor(0.5))
+				.hover(|s| s.background(C_FOCUS.with_alpha_factor(0.05)))
+				.focus(|s| s.border_color(C_FOCUS).outline_color(C_FOCUS))
+				.focus_visible(|s| s.outline(1))
+				.class(PlaceholderTextClass, |s| {
+					s.color(C_TEXT_MAIN.with_alpha_factor(0.5))
+				})
+		})
 }
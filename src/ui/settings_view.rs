@@ -1,14 +1,24 @@
 use floem::{
-	event::EventListener,
+	event::{Event, EventListener},
 	reactive::create_signal,
 	style::Position,
 	view::View,
 	views::{container, h_stack, label, scroll, tab, v_stack, Decorators},
+	EventPropagation,
 };
 
+use vault::config::Config;
+
 use crate::ui::{
 	colors::*,
-	primitives::{button::tab_button, styles},
+	database_view::database_view,
+	password_age_view::password_age_view,
+	primitives::{
+		button::tab_button,
+		styles,
+		tooltip::{tooltip_view, TooltipSignals},
+	},
+	templates_view::templates_view,
 };
 
 #[derive(Clone, Copy, Eq, Hash, PartialEq)]
@@ -16,6 +26,7 @@ pub enum Tabs {
 	General,
 	Editing,
 	Database,
+	Passwords,
 }
 
 impl std::fmt::Display for Tabs {
@@ -24,22 +35,31 @@ impl std::fmt::Display for Tabs {
 			Tabs::General => write!(f, "General"),
 			Tabs::Editing => write!(f, "Editing"),
 			Tabs::Database => write!(f, "Database"),
+			Tabs::Passwords => write!(f, "Passwords"),
 		}
 	}
 }
 
 pub const TABBAR_HEIGHT: f64 = 63.0;
 
-pub fn settings_view() -> impl View {
-	let tabs = vec![Tabs::General, Tabs::Editing, Tabs::Database]
-		.into_iter()
-		.collect::<im::Vector<Tabs>>();
+pub fn settings_view(config: Config) -> impl View {
+	let tabs = vec![
+		Tabs::General,
+		Tabs::Editing,
+		Tabs::Database,
+		Tabs::Passwords,
+	]
+	.into_iter()
+	.collect::<im::Vector<Tabs>>();
 	let (tabs, _set_tabs) = create_signal(tabs);
 	let (active_tab, set_active_tab) = create_signal(0);
 
+	let tooltip_signals = TooltipSignals::new();
+
 	let settings_icon = include_str!("./icons/settings.svg");
 	let editing_icon = include_str!("./icons/editing.svg");
 	let database_icon = include_str!("./icons/database.svg");
+	let password_icon = include_str!("./icons/password.svg");
 
 	let tabs_bar = h_stack((
 		tab_button(
@@ -63,6 +83,13 @@ pub fn settings_view() -> impl View {
 			set_active_tab,
 			active_tab,
 		),
+		tab_button(
+			String::from(password_icon),
+			Tabs::Passwords,
+			tabs,
+			set_active_tab,
+			active_tab,
+		),
 	))
 	.style(|s| {
 		s.flex_row()
@@ -79,11 +106,12 @@ pub fn settings_view() -> impl View {
 			move || active_tab.get(),
 			move || tabs.get(),
 			|it| *it,
-			|it| {
+			move |it| {
 				match it {
 				Tabs::General => container(label(move || String::from("General\nGeneral\nGeneral\nGeneral\nGeneral\nGeneral\nGeneral\nGeneral\nGeneral\nGeneral\nGeneral\nGeneral\nGeneral\nGeneral\nGeneral\nGeneral\n")).style(|s| s.padding(8.0))),
-				Tabs::Editing => container(label(move || String::from("Editing")).style(|s| s.padding(8.0))),
-				Tabs::Database => container(label(move || String::from("Database")).style(|s| s.padding(8.0))),
+				Tabs::Editing => container(templates_view(tooltip_signals, config.clone())),
+				Tabs::Database => container(database_view(tooltip_signals, config.clone())),
+				Tabs::Passwords => container(password_age_view(tooltip_signals, config.clone())),
 			}
 			},
 		).style(|s| s.flex_col().items_start().padding_bottom(10.0))).style(|s| {
@@ -96,8 +124,20 @@ pub fn settings_view() -> impl View {
 	}))
 	.style(|s| s.position(Position::Absolute).inset_top(TABBAR_HEIGHT).inset_bottom(0.0).width_full());
 
-	let settings_view = v_stack((tabs_bar, main_content))
-		.style(|s| s.width_full().height_full().gap(0, 5));
+	let settings_view =
+		v_stack((tabs_bar, main_content, tooltip_view(tooltip_signals)))
+			.style(|s| s.width_full().height_full().gap(0, 5))
+			.on_event(EventListener::PointerMove, move |event| {
+				let pos = match event {
+					Event::PointerMove(p) => p.pos,
+					_ => (0.0, 0.0).into(),
+				};
+				tooltip_signals.mouse_pos.set((pos.x, pos.y));
+				EventPropagation::Continue
+			})
+			.on_resize(move |event| {
+				tooltip_signals.window_size.set((event.x1, event.y1));
+			});
 
 	match std::env::var("DEBUG") {
 		Ok(_) => {
@@ -0,0 +1,210 @@
+use floem::{
+	reactive::{create_rw_signal, RwSignal},
+	style::{AlignItems, Display},
+	view::View,
+	views::{h_stack, label, list, v_stack, Decorators},
+};
+
+use vault::{
+	config::Config,
+	db::{DynamicFieldKind, EntryKind},
+	template::{EntryTemplate, TemplateField},
+};
+
+use crate::ui::{
+	colors::*,
+	details::new_entry::DraftField,
+	primitives::{
+		button::text_button, input_field::input_field, tooltip::TooltipSignals,
+	},
+};
+
+const TEMPLATE_WIDTH: f64 = 400.0;
+
+fn template_line(
+	template: EntryTemplate,
+	templates: RwSignal<Vec<EntryTemplate>>,
+	error: RwSignal<String>,
+	tooltip_signals: TooltipSignals,
+	config: Config,
+) -> impl View {
+	let title = template.title.clone();
+	let kind = template.kind;
+	let builtin = template.builtin;
+	let fields = template
+		.fields
+		.iter()
+		.map(|field| format!("{} ({})", field.title, field.kind))
+		.collect::<Vec<String>>()
+		.join(", ");
+
+	v_stack((
+		h_stack((
+			label(move || title.clone()).style(|s| s.flex_grow(1.0)),
+			label(move || {
+				if builtin {
+					format!("{}, built-in", kind)
+				} else {
+					kind.to_string()
+				}
+			})
+			.style(|s| s.color(C_TEXT_MAIN_INACTIVE).font_size(11.0)),
+			text_button(
+				|| String::from("Remove"),
+				String::from("Remove this template"),
+				tooltip_signals,
+				move |_| {
+					match config.db.write().remove_template(&template.title) {
+						Ok(()) => error.set(String::from("")),
+						Err(err) => error.set(err.to_string()),
+					}
+					templates.set(config.db.read().get_templates());
+				},
+			)
+			.style(move |s| s.apply_if(builtin, |s| s.display(Display::None))),
+		))
+		.style(|s| s.items_center().gap(6.0, 0.0).width_full()),
+		label(move || fields.clone())
+			.style(|s| s.color(C_TEXT_SIDE_INACTIVE).font_size(10.0).width_full()),
+	))
+	.style(|s| {
+		s.width(TEMPLATE_WIDTH)
+			.padding(6)
+			.border_bottom(1)
+			.border_color(C_BG_MAIN_BORDER)
+	})
+}
+
+pub fn templates_view(
+	tooltip_signals: TooltipSignals,
+	config: Config,
+) -> impl View {
+	let templates = create_rw_signal(config.db.read().get_templates());
+	let error = create_rw_signal(String::from(""));
+
+	let title = create_rw_signal(String::from(""));
+	let kind = create_rw_signal(EntryKind::Login);
+	let fields = create_rw_signal(im::vector![DraftField::new("")]);
+
+	let config_save = config.clone();
+
+	v_stack((
+		label(|| "Entry templates").style(|s| s.font_size(16.0)),
+		label(|| "Templates pre-fill the fields of new entries.")
+			.style(|s| s.color(C_TEXT_MAIN_INACTIVE).margin_bottom(5)),
+		list(
+			move || templates.get(),
+			|template| template.title.clone(),
+			move |template| {
+				template_line(
+					template,
+					templates,
+					error,
+					tooltip_signals,
+					config.clone(),
+				)
+			},
+		)
+		.style(|s| s.flex_col().margin_bottom(15)),
+		label(|| "New template").style(|s| s.font_size(14.0)),
+		h_stack((
+			input_field(title).placeholder("Template name").style(|s| s.width(200)),
+			text_button(
+				move || kind.get().to_string(),
+				String::from("Switch between a login and a secure note"),
+				tooltip_signals,
+				move |_| {
+					kind.set(match kind.get() {
+						EntryKind::Login => EntryKind::SecureNote,
+						EntryKind::SecureNote => EntryKind::Login,
+					});
+				},
+			),
+		))
+		.style(|s| s.items_center().gap(4.0, 0.0)),
+		list(
+			move || fields.get(),
+			|field| field.id,
+			move |field| {
+				h_stack((
+					input_field(field.title)
+						.placeholder("Field name")
+						.style(|s| s.width(200)),
+					text_button(
+						move || field.kind.get().to_string(),
+						String::from("Change the type of this field"),
+						tooltip_signals,
+						move |_| {
+							let pos = DynamicFieldKind::ALL
+								.iter()
+								.position(|item| *item == field.kind.get())
+								.unwrap_or(0);
+							field.kind.set(
+								DynamicFieldKind::ALL[(pos + 1) % DynamicFieldKind::ALL.len()],
+							);
+						},
+					),
+					text_button(
+						|| String::from("×"),
+						String::from("Remove this field"),
+						tooltip_signals,
+						move |_| {
+							fields.update(|fields| fields.retain(|item| item.id != field.id));
+						},
+					),
+				))
+				.style(|s| s.items_center().gap(4.0, 0.0))
+			},
+		)
+		.style(|s| s.flex_col().gap(0.0, 4.0)),
+		label(move || error.get()).style(move |s| {
+			s.color(C_ERROR)
+				.display(Display::None)
+				.apply_if(!error.get().is_empty(), |s| s.display(Display::Flex))
+		}),
+		h_stack((
+			text_button(
+				|| String::from("Add field"),
+				String::from("Add a field to the template"),
+				tooltip_signals,
+				move |_| {
+					fields.update(|fields| fields.push_back(DraftField::new("")));
+				},
+			),
+			text_button(
+				|| String::from("Save template"),
+				String::from("Store the template with the database"),
+				tooltip_signals,
+				move |_| {
+					let template = EntryTemplate {
+						title: title.get(),
+						kind: kind.get(),
+						fields: fields
+							.get()
+							.iter()
+							.filter(|field| !field.title.get().trim().is_empty())
+							.map(|field| TemplateField {
+								title: field.title.get().trim().to_string(),
+								kind: field.kind.get(),
+							})
+							.collect(),
+						builtin: false,
+					};
+
+					match config_save.db.write().add_template(template) {
+						Ok(()) => {
+							title.set(String::from(""));
+							kind.set(EntryKind::Login);
+							fields.set(im::vector![DraftField::new("")]);
+							error.set(String::from(""));
+						}
+						Err(err) => error.set(err.to_string()),
+					}
+					templates.set(config_save.db.read().get_templates());
+				},
+			),
+		))
+		.style(|s| s.align_items(AlignItems::Center)),
+	))
+	.style(|s| s.padding(8.0).gap(0.0, 5.0))
+}
@@ -9,7 +9,7 @@ use floem::{
 	EventPropagation,
 };
 
-use crate::db::DbFields;
+use vault::db::DbFields;
 
 thread_local! {
 	pub(crate) static OPEN_WINDOWS: RefCell<Vec<(String, WindowId)>> = RefCell::new(Vec::new());